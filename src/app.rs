@@ -1,15 +1,23 @@
+use crate::app::components::map_theme::MapThemePreset;
+use crate::app::components::quiz::scheduler::ReviewScheduler;
 use crate::app::components::quiz::stats::QuizStats;
-use crate::app::persistence::{persist_state, restore_state};
+use crate::app::persistence::autosave::{AutosaveInterval, Autosaver};
+use crate::app::persistence::bundle::ProgressBundle;
+use crate::app::persistence::profiles::{Profile, Profiles};
+use crate::app::persistence::restore_state;
 use crate::app::views::explore::{ExploreState, ExploreStatePersist};
 use crate::app::views::main_menu::MainMenuState;
+use crate::app::views::profile_menu::ProfileMenuState;
 use crate::app::views::quiz_menu::{QuizMenuState, QuizMenuStatePersist};
 use crate::app::views::quiz_run::{QuizRunState, QuizRunStatePersist};
+use crate::app::views::statistics::StatisticsState;
 use crate::app::views::study_menu::StudyMenuState;
 use crate::app::views::*;
 use eframe::{App, Frame};
-use egui::Context;
+use egui::{Align2, Context, Event};
 use persistence::persistent_object::PersistentObject;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use views::UIView;
 
@@ -22,13 +30,36 @@ mod views;
 pub struct WorldStudyApp {
     current_view: UIView,
     quiz_history: Vec<QuizStats>,
+    review_scheduler: ReviewScheduler,
+    map_theme: MapThemePreset,
+    autosave_interval: AutosaveInterval,
 
     // View states
+    #[allow(dead_code)]
     main_menu_state: MainMenuState,
+    #[allow(dead_code)]
     study_menu_state: StudyMenuState,
     explore_state: ExploreState,
     quiz_menu_state: QuizMenuState,
     quiz_run_state: QuizRunState,
+    statistics_state: StatisticsState,
+    profile_menu_state: ProfileMenuState,
+
+    profiles: Profiles,
+    /// The profile being studied with, nothing is saved until one is opened.
+    profile: Option<Profile>,
+    /// Shown until dismissed when the save file had to be recovered or can't be written.
+    load_notice: Option<String>,
+    /// `None` while no profile is open or the profile's save is read-only.
+    autosaver: Option<Autosaver>,
+    /// Set when something changed that should be saved right away instead of on the timer.
+    dirty: bool,
+    /// Counts frames that may have changed the saved state, autosaves are skipped while it's
+    /// the same as when last saved.
+    changes: u64,
+    saved_changes: u64,
+    /// Settings as of the last change, a difference is saved right away.
+    settings: Settings,
 
     // Diagnostics
     update_time: Duration,
@@ -36,10 +67,41 @@ pub struct WorldStudyApp {
 
 impl WorldStudyApp {
     pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        if let Some(state) = restore_state() {
-            Self::load_state(state)
-        } else {
-            Self::default()
+        let mut app = Self {
+            current_view: UIView::ProfileMenu,
+            profiles: Profiles::load(),
+            ..Self::default()
+        };
+
+        // With several people sharing the app everyone picks their own profile on startup.
+        if let [profile] = app.profiles.list() {
+            app.open_profile(profile.clone());
+        }
+        app
+    }
+
+    /// Saves the current profile and replaces the whole app state with the profile's.
+    pub fn open_profile(&mut self, profile: Profile) {
+        self.flush_save();
+
+        let directory = self.profiles.directory(&profile.id);
+        let restored = restore_state(&directory);
+        let mut app = restored.state.map(Self::load_state).unwrap_or_default();
+        app.load_notice = restored.notice;
+        // A save from a newer version would lose whatever this version doesn't know about.
+        app.autosaver = (!restored.read_only).then(|| Autosaver::new(directory));
+
+        self.profiles.set_last_used(&profile.id);
+        app.profiles = std::mem::take(&mut self.profiles);
+        app.profile = Some(profile);
+        *self = app;
+    }
+
+    /// Writes the current profile's state and waits until it's on disk.
+    pub fn flush_save(&mut self) {
+        let state = self.save_state();
+        if let Some(autosaver) = &mut self.autosaver {
+            autosaver.finish(state);
         }
     }
 
@@ -47,15 +109,133 @@ impl WorldStudyApp {
         self.current_view = target_view
     }
 
+    fn render_load_notice(&mut self, ctx: &Context) {
+        let Some(notice) = &self.load_notice else {
+            return;
+        };
+
+        let mut dismissed = false;
+        egui::Window::new("Save file")
+            .anchor(Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+            .collapsible(false)
+            .resizable(false)
+            .max_width(320.0)
+            .show(ctx, |ui| {
+                ui.label(notice);
+                dismissed = ui.button("Dismiss").clicked();
+            });
+
+        if dismissed {
+            self.load_notice = None;
+        }
+    }
+
+    /// Writes the open profile's progress and settings to a portable file.
+    pub fn export_progress(&self) -> Result<PathBuf, String> {
+        let profile = self.profile.as_ref().ok_or("No profile is open")?;
+        ProgressBundle::from_state(self.save_state(), &profile.name).export()
+    }
+
+    /// Merges a progress file into the open profile, returns how many quizzes were new.
+    ///
+    /// The profile's settings are only replaced with `import_settings`, see
+    /// [`ProgressBundle::merge_into`].
+    pub fn import_progress(&mut self, path: &Path, import_settings: bool) -> Result<usize, String> {
+        if self.profile.is_none() {
+            return Err("No profile is open".to_string());
+        }
+        if self.autosaver.is_none() {
+            return Err("The profile is read-only, progress can't be imported".to_string());
+        }
+
+        let bundle = ProgressBundle::read(path)?;
+        let mut state = self.save_state();
+        let added = bundle.merge_into(&mut state, import_settings);
+
+        let mut app = Self::load_state(state);
+        app.current_view = self.current_view;
+        app.profiles = std::mem::take(&mut self.profiles);
+        app.profile = self.profile.take();
+        app.autosaver = self.autosaver.take();
+        app.dirty = true;
+        *self = app;
+        Ok(added)
+    }
+
     pub fn log_quiz_stats(&mut self, stats: QuizStats) {
+        self.review_scheduler.record(&stats);
         self.quiz_history.push(stats);
+        self.dirty = true;
+    }
+
+    fn settings(&self) -> Settings {
+        Settings {
+            map_theme: self.map_theme,
+            autosave_interval: self.autosave_interval,
+            quiz_menu: self.quiz_menu_state.save_state(),
+        }
+    }
+
+    /// The saved state only changes in response to input, frames with nothing but pointer
+    /// movement leave it as it was.
+    fn track_changes(&mut self, ctx: &Context) {
+        let had_input = ctx.input(|input| {
+            input.events.iter().any(|event| {
+                !matches!(
+                    event,
+                    Event::PointerMoved(_) | Event::MouseMoved(_) | Event::PointerGone
+                )
+            })
+        });
+        if !had_input {
+            return;
+        }
+
+        self.changes += 1;
+        let settings = self.settings();
+        if settings != self.settings {
+            self.settings = settings;
+            self.dirty = true;
+        }
     }
+
+    fn autosave(&mut self, ctx: &Context) {
+        let interval = self.autosave_interval.duration();
+        let Some(due) = self.autosaver.as_mut().map(|saver| saver.is_due(interval)) else {
+            return;
+        };
+
+        // Without a repaint the timer would only be checked on the next input.
+        ctx.request_repaint_after(interval);
+        if self.dirty || (due && self.changes != self.saved_changes) {
+            self.dirty = false;
+            self.saved_changes = self.changes;
+            let state = self.save_state();
+            if let Some(autosaver) = &mut self.autosaver {
+                autosaver.save(state);
+            }
+        }
+    }
+}
+
+/// The parts of the state changed through settings widgets.
+#[derive(Debug, Default, PartialEq)]
+struct Settings {
+    map_theme: MapThemePreset,
+    autosave_interval: AutosaveInterval,
+    quiz_menu: QuizMenuStatePersist,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct AppState {
     last_view: UIView,
     quiz_history: Vec<QuizStats>,
+    #[serde(default)]
+    review_scheduler: ReviewScheduler,
+    #[serde(default)]
+    map_theme: MapThemePreset,
+    #[serde(default)]
+    autosave_interval: AutosaveInterval,
     explore_state: ExploreStatePersist,
     quiz_menu_state: QuizMenuStatePersist,
     quiz_run_state: QuizRunStatePersist,
@@ -66,8 +246,15 @@ impl PersistentObject for WorldStudyApp {
 
     fn save_state(&self) -> Self::PersistentState {
         AppState {
-            last_view: self.current_view,
+            // Opening a profile shouldn't land in the profile picker.
+            last_view: match self.current_view {
+                UIView::ProfileMenu => UIView::MainMenu,
+                view => view,
+            },
             quiz_history: self.quiz_history.clone(),
+            review_scheduler: self.review_scheduler.clone(),
+            map_theme: self.map_theme,
+            autosave_interval: self.autosave_interval,
             explore_state: self.explore_state.save_state(),
             quiz_menu_state: self.quiz_menu_state.save_state(),
             quiz_run_state: self.quiz_run_state.save_state(),
@@ -75,16 +262,37 @@ impl PersistentObject for WorldStudyApp {
     }
 
     fn load_state(state: Self::PersistentState) -> Self {
-        Self {
+        let review_scheduler = if state.review_scheduler.is_empty() {
+            ReviewScheduler::from_history(&state.quiz_history)
+        } else {
+            state.review_scheduler
+        };
+
+        let mut app = Self {
             current_view: state.last_view,
             quiz_history: state.quiz_history,
+            review_scheduler,
+            map_theme: state.map_theme,
+            autosave_interval: state.autosave_interval,
             main_menu_state: MainMenuState,
             study_menu_state: StudyMenuState,
             explore_state: ExploreState::load_state(state.explore_state),
             quiz_menu_state: QuizMenuState::load_state(state.quiz_menu_state),
             quiz_run_state: QuizRunState::load_state(state.quiz_run_state),
+            statistics_state: StatisticsState::default(),
+            profile_menu_state: ProfileMenuState::default(),
+            profiles: Profiles::default(),
+            profile: None,
+            load_notice: None,
+            autosaver: None,
+            dirty: false,
+            changes: 0,
+            saved_changes: 0,
+            settings: Settings::default(),
             update_time: Duration::from_secs(0),
-        }
+        };
+        app.settings = app.settings();
+        app
     }
 }
 
@@ -94,6 +302,7 @@ impl App for WorldStudyApp {
         profiling::scope!("frame");
 
         let before_update = Instant::now();
+        self.map_theme.theme().set(ctx);
 
         match self.current_view {
             UIView::MainMenu => main_menu::render(ctx, self),
@@ -101,7 +310,12 @@ impl App for WorldStudyApp {
             UIView::Explore => explore::render(ctx, self),
             UIView::QuizMenu => quiz_menu::render(ctx, self),
             UIView::QuizRun => quiz_run::render(ctx, self),
+            UIView::Statistics => statistics::render(ctx, self),
+            UIView::ProfileMenu => profile_menu::render(ctx, self),
         }
+        self.render_load_notice(ctx);
+        self.track_changes(ctx);
+        self.autosave(ctx);
 
         self.update_time = before_update.elapsed();
 
@@ -110,7 +324,6 @@ impl App for WorldStudyApp {
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
-        let state = self.save_state();
-        persist_state(state);
+        self.flush_save();
     }
 }
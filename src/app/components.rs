@@ -1,5 +1,8 @@
+pub mod country_autocomplete;
 pub mod custom_button;
+pub mod daily_chart;
+pub mod map_theme;
 pub mod quiz;
-mod settings_slider;
+pub mod settings_slider;
 mod value_reset_button;
 pub mod world_map;
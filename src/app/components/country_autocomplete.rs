@@ -0,0 +1,176 @@
+use crate::app::components::quiz::answer_matching::normalize;
+use crate::get_data;
+use egui::{Area, Frame, Id, Key, Modifiers, Order, Response, TextEdit, Ui};
+
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Single line text field suggesting matching country names while typing.
+///
+/// Suggestions are navigated with the arrow keys and accepted with Tab, Enter or a click.
+#[derive(Debug)]
+pub struct CountryAutocomplete {
+    id_salt: String,
+    enabled: bool,
+}
+
+#[derive(Debug)]
+pub struct CountryAutocompleteResponse {
+    pub response: Response,
+    /// Whether the text was filled in from a suggestion this frame.
+    pub completed: bool,
+}
+
+impl CountryAutocomplete {
+    pub fn new(id_salt: impl Into<String>) -> Self {
+        Self {
+            id_salt: id_salt.into(),
+            enabled: true,
+        }
+    }
+
+    /// Without suggestions this is a plain text field.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn draw(self, ui: &mut Ui, text: &mut String) -> CountryAutocompleteResponse {
+        let id = ui.make_persistent_id(&self.id_salt);
+        let text_edit_id = id.with("text_edit");
+        let highlight_id = id.with("highlighted");
+
+        let suggestions = if self.enabled {
+            country_suggestions(text)
+        } else {
+            Vec::new()
+        };
+        let had_focus = ui.memory(|memory| memory.has_focus(text_edit_id));
+        let mut highlighted = ui
+            .data(|data| data.get_temp::<usize>(highlight_id))
+            .filter(|index| *index < suggestions.len());
+
+        let mut accepted = None;
+        if had_focus && !suggestions.is_empty() {
+            let count = suggestions.len();
+            ui.input_mut(|input| {
+                if input.consume_key(Modifiers::NONE, Key::ArrowDown) {
+                    highlighted = Some(highlighted.map_or(0, |index| (index + 1) % count));
+                }
+                if input.consume_key(Modifiers::NONE, Key::ArrowUp) {
+                    highlighted =
+                        Some(highlighted.map_or(count - 1, |index| (index + count - 1) % count));
+                }
+                if input.consume_key(Modifiers::NONE, Key::Tab) {
+                    accepted = Some(highlighted.unwrap_or(0));
+                }
+                if input.key_pressed(Key::Enter) {
+                    accepted = accepted.or(highlighted);
+                }
+            });
+        }
+
+        // Filled in before the text edit, so Enter submits the completed name right away.
+        if let Some(index) = accepted {
+            *text = suggestions[index].clone();
+        }
+
+        let mut response = ui.add(TextEdit::singleline(text).id(text_edit_id));
+        if response.changed() {
+            highlighted = None;
+        }
+
+        if had_focus
+            && accepted.is_none()
+            && !suggestions.is_empty()
+            && let Some(index) = draw_suggestions(ui, id, &response, &suggestions, highlighted)
+        {
+            *text = suggestions[index].clone();
+            accepted = Some(index);
+        }
+
+        let completed = accepted.is_some();
+        if completed {
+            highlighted = None;
+            response.mark_changed();
+        }
+        ui.data_mut(|data| match highlighted {
+            Some(index) => data.insert_temp(highlight_id, index),
+            None => data.remove::<usize>(highlight_id),
+        });
+
+        CountryAutocompleteResponse {
+            response,
+            completed,
+        }
+    }
+}
+
+impl CountryAutocompleteResponse {
+    /// Marks the text as assisted once a suggestion was taken, typing afterwards clears the mark.
+    pub fn update_assisted(&self, assisted: &mut bool) {
+        if self.completed {
+            *assisted = true;
+        } else if self.response.changed() {
+            *assisted = false;
+        }
+    }
+}
+
+/// Draws the dropdown below the text field and returns the index of a clicked suggestion.
+fn draw_suggestions(
+    ui: &Ui,
+    id: Id,
+    text_edit_response: &Response,
+    suggestions: &[String],
+    highlighted: Option<usize>,
+) -> Option<usize> {
+    let mut clicked = None;
+
+    Area::new(id.with("suggestions"))
+        .order(Order::Foreground)
+        .fixed_pos(text_edit_response.rect.left_bottom())
+        .show(ui.ctx(), |ui| {
+            Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_min_width(text_edit_response.rect.width());
+                for (index, suggestion) in suggestions.iter().enumerate() {
+                    if ui
+                        .selectable_label(highlighted == Some(index), suggestion)
+                        .clicked()
+                    {
+                        clicked = Some(index);
+                    }
+                }
+            });
+        });
+
+    clicked
+}
+
+/// Country names starting with the typed text first, then names with a word starting with it.
+fn country_suggestions(text: &str) -> Vec<String> {
+    let query = normalize(text);
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut suggestions: Vec<(bool, String)> = get_data()
+        .countries_iter()
+        .filter_map(|country| {
+            let name = normalize(&country.common_name);
+            if name == query {
+                return None;
+            }
+
+            let is_prefix = name.starts_with(&query);
+            let word_prefix = name.split(' ').any(|word| word.starts_with(&query));
+            (is_prefix || word_prefix).then(|| (!is_prefix, country.common_name.clone()))
+        })
+        .collect();
+
+    suggestions.sort();
+    suggestions
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name)
+        .collect()
+}
@@ -0,0 +1,129 @@
+use crate::utils::time::format_day;
+use egui::{Align2, Color32, CornerRadius, FontId, Pos2, Rect, Sense, Stroke, Ui, Vec2};
+
+const CHART_HEIGHT: f32 = 120.0;
+const AXIS_LABEL_WIDTH: f32 = 45.0;
+const DATE_LABEL_HEIGHT: f32 = 16.0;
+const BAR_GAP: f32 = 1.0;
+/// Narrow bars over a long range of days shrink the gap so it never takes up the whole bar.
+const MAX_BAR_GAP_SHARE: f32 = 0.25;
+const BAR_COLOR: Color32 = Color32::from_rgb(86, 140, 200);
+const HOVER_COLOR: Color32 = Color32::from_rgb(130, 180, 235);
+
+/// Bar chart with one bar per day, days without a value are left empty.
+#[derive(Debug)]
+pub struct DailyChart {
+    first_day: i64,
+    last_day: i64,
+    max_value: Option<f32>,
+    unit: String,
+}
+
+impl DailyChart {
+    pub fn new(first_day: i64, last_day: i64) -> Self {
+        Self {
+            first_day,
+            last_day: last_day.max(first_day),
+            max_value: None,
+            unit: String::new(),
+        }
+    }
+
+    /// Fixes the top of the value axis, otherwise the largest value is used.
+    pub fn max_value(mut self, max_value: f32) -> Self {
+        self.max_value = Some(max_value);
+        self
+    }
+
+    pub fn unit(mut self, unit: impl Into<String>) -> Self {
+        self.unit = unit.into();
+        self
+    }
+
+    pub fn draw(self, ui: &mut Ui, values: &[(i64, f32)]) {
+        let size = Vec2::new(ui.available_width(), CHART_HEIGHT + DATE_LABEL_HEIGHT);
+        let (rect, response) = ui.allocate_exact_size(size, Sense::hover());
+        let painter = ui.painter_at(rect);
+        let visuals = ui.visuals();
+        let text_color = visuals.weak_text_color();
+        let font = FontId::proportional(11.0);
+
+        let plot_rect = Rect::from_min_max(
+            Pos2::new(rect.left() + AXIS_LABEL_WIDTH, rect.top()),
+            Pos2::new(rect.right(), rect.top() + CHART_HEIGHT),
+        );
+        painter.rect_stroke(
+            plot_rect,
+            CornerRadius::ZERO,
+            Stroke::new(1.0, visuals.widgets.noninteractive.bg_stroke.color),
+            egui::StrokeKind::Inside,
+        );
+
+        let max_value = self
+            .max_value
+            .unwrap_or_else(|| values.iter().map(|(_, value)| *value).fold(0.0, f32::max))
+            .max(f32::EPSILON);
+        painter.text(
+            Pos2::new(plot_rect.left() - 4.0, plot_rect.top()),
+            Align2::RIGHT_TOP,
+            format!("{:.0}{}", max_value, self.unit),
+            font.clone(),
+            text_color,
+        );
+        painter.text(
+            Pos2::new(plot_rect.left() - 4.0, plot_rect.bottom()),
+            Align2::RIGHT_BOTTOM,
+            format!("0{}", self.unit),
+            font.clone(),
+            text_color,
+        );
+
+        let date_y = plot_rect.bottom() + 2.0;
+        painter.text(
+            Pos2::new(plot_rect.left(), date_y),
+            Align2::LEFT_TOP,
+            format_day(self.first_day),
+            font.clone(),
+            text_color,
+        );
+        painter.text(
+            Pos2::new(plot_rect.right(), date_y),
+            Align2::RIGHT_TOP,
+            format_day(self.last_day),
+            font,
+            text_color,
+        );
+
+        let day_count = (self.last_day - self.first_day + 1) as f32;
+        let bar_width = plot_rect.width() / day_count;
+        let bar_gap = BAR_GAP.min(bar_width * MAX_BAR_GAP_SHARE);
+        let hovered_day = response.hover_pos().map(|position| {
+            self.first_day + ((position.x - plot_rect.left()) / bar_width).floor() as i64
+        });
+
+        for (day, value) in values {
+            if *day < self.first_day || *day > self.last_day {
+                continue;
+            }
+
+            let left = plot_rect.left() + (day - self.first_day) as f32 * bar_width;
+            let height = (value / max_value).clamp(0.0, 1.0) * plot_rect.height();
+            let bar_rect = Rect::from_min_max(
+                Pos2::new(left + bar_gap / 2.0, plot_rect.bottom() - height),
+                Pos2::new(left + bar_width - bar_gap / 2.0, plot_rect.bottom()),
+            );
+            let color = if hovered_day == Some(*day) {
+                HOVER_COLOR
+            } else {
+                BAR_COLOR
+            };
+            painter.rect_filled(bar_rect, CornerRadius::ZERO, color);
+        }
+
+        if let Some(day) = hovered_day
+            && let Some((_, value)) = values.iter().find(|(value_day, _)| *value_day == day)
+        {
+            response.on_hover_text(format!("{}: {:.1}{}", format_day(day), value, self.unit));
+        }
+    }
+}
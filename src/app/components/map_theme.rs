@@ -0,0 +1,116 @@
+use egui::{Color32, Context, Id};
+use serde::{Deserialize, Serialize};
+
+/// Colours used to draw the world map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MapTheme {
+    pub land: Color32,
+    pub land_hovered: Color32,
+    pub land_selected: Color32,
+    pub border: Color32,
+    pub water: Color32,
+    pub capital: Color32,
+    pub capital_hovered: Color32,
+    pub capital_selected: Color32,
+    pub capital_outline: Color32,
+}
+
+impl Default for MapTheme {
+    fn default() -> Self {
+        MapThemePreset::default().theme()
+    }
+}
+
+impl MapTheme {
+    fn id() -> Id {
+        Id::new("map_theme")
+    }
+
+    /// The theme every map is drawn with, set once per frame by the app.
+    pub fn get(ctx: &Context) -> Self {
+        ctx.data(|data| data.get_temp(Self::id()))
+            .unwrap_or_default()
+    }
+
+    pub fn set(self, ctx: &Context) {
+        ctx.data_mut(|data| data.insert_temp(Self::id(), self));
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MapThemePreset {
+    #[default]
+    Classic,
+    Dark,
+    /// Based on the Okabe-Ito palette, distinguishable with all common forms of colour blindness.
+    ColorBlindSafe,
+    /// Black, white, yellow and blue only, for low vision and colour blindness.
+    HighContrast,
+}
+
+impl MapThemePreset {
+    pub const ALL: [Self; 4] = [
+        Self::Classic,
+        Self::Dark,
+        Self::ColorBlindSafe,
+        Self::HighContrast,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::Dark => "Dark",
+            Self::ColorBlindSafe => "Color-blind safe",
+            Self::HighContrast => "High contrast",
+        }
+    }
+
+    pub fn theme(&self) -> MapTheme {
+        match self {
+            Self::Classic => MapTheme {
+                land: Color32::from_rgb(222, 163, 139),
+                land_hovered: Color32::from_rgb(249, 130, 132),
+                land_selected: Color32::from_rgb(255, 196, 132),
+                border: Color32::from_rgb(108, 86, 113),
+                water: Color32::from_rgb(172, 204, 228),
+                capital: Color32::from_rgb(255, 0, 0),
+                capital_hovered: Color32::from_rgb(0, 255, 0),
+                capital_selected: Color32::from_rgb(0, 0, 255),
+                capital_outline: Color32::BLACK,
+            },
+            Self::Dark => MapTheme {
+                land: Color32::from_rgb(70, 74, 82),
+                land_hovered: Color32::from_rgb(100, 106, 118),
+                land_selected: Color32::from_rgb(140, 122, 72),
+                border: Color32::from_rgb(28, 30, 34),
+                water: Color32::from_rgb(24, 32, 44),
+                capital: Color32::from_rgb(232, 112, 92),
+                capital_hovered: Color32::from_rgb(140, 220, 140),
+                capital_selected: Color32::from_rgb(120, 170, 250),
+                capital_outline: Color32::from_rgb(10, 10, 10),
+            },
+            Self::ColorBlindSafe => MapTheme {
+                land: Color32::from_rgb(224, 224, 218),
+                land_hovered: Color32::from_rgb(86, 180, 233),
+                land_selected: Color32::from_rgb(230, 159, 0),
+                border: Color32::from_rgb(64, 64, 64),
+                water: Color32::from_rgb(170, 190, 205),
+                capital: Color32::from_rgb(213, 94, 0),
+                capital_hovered: Color32::from_rgb(0, 158, 115),
+                capital_selected: Color32::from_rgb(0, 114, 178),
+                capital_outline: Color32::BLACK,
+            },
+            Self::HighContrast => MapTheme {
+                land: Color32::WHITE,
+                land_hovered: Color32::from_rgb(255, 221, 0),
+                land_selected: Color32::from_rgb(0, 90, 200),
+                border: Color32::BLACK,
+                water: Color32::from_rgb(60, 60, 60),
+                capital: Color32::BLACK,
+                capital_hovered: Color32::from_rgb(255, 221, 0),
+                capital_selected: Color32::from_rgb(0, 90, 200),
+                capital_outline: Color32::WHITE,
+            },
+        }
+    }
+}
@@ -1,19 +1,32 @@
+use crate::app::components::quiz::scheduler::ReviewScheduler;
 use crate::app::components::quiz::stats::QuizStats;
 use crate::app::persistence::persistent_object::PersistentObject;
 use egui::Ui;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
+use types::capital_location::{CapitalLocationQuiz, CapitalLocationQuizState};
+use types::country_capital::{CountryCapitalQuiz, CountryCapitalQuizState};
+use types::country_flag::{CountryFlagQuiz, CountryFlagQuizState};
+use types::country_neighbors::{CountryNeighborsQuiz, CountryNeighborsQuizState};
+use types::country_shape::{CountryShapeQuiz, CountryShapeQuizState};
 use types::flag_name_country::{FlagNameCountryQuiz, FlagNameCountryQuizState};
+use types::locate_country::{LocateCountryQuiz, LocateCountryQuizState};
+use types::world_countries::{WorldCountriesQuiz, WorldCountriesQuizState};
 
+pub mod answer_matching;
+pub mod distractors;
+pub mod region_filter;
+pub mod scheduler;
 pub mod settings;
 pub mod stats;
 pub mod types;
 
 pub trait QuizTrait: Debug + Default + PersistentObject {
     fn render(&mut self, ui: &mut Ui) -> Option<bool>;
-    fn start(&mut self);
+    fn start(&mut self, scheduler: &ReviewScheduler);
     fn finish(&mut self, success: bool);
     fn has_started(&self) -> bool;
+    #[allow(dead_code)]
     fn is_successful(&self) -> Option<bool>;
     fn reset(&mut self);
     fn collect_stats(&self) -> Option<QuizStats>;
@@ -22,12 +35,53 @@ pub trait QuizTrait: Debug + Default + PersistentObject {
 #[derive(Debug)]
 pub enum Quiz {
     FlagNameCountry(FlagNameCountryQuiz),
+    LocateCountry(LocateCountryQuiz),
+    CountryCapital(CountryCapitalQuiz),
+    CapitalLocation(CapitalLocationQuiz),
+    CountryFlag(CountryFlagQuiz),
+    CountryShape(CountryShapeQuiz),
+    CountryNeighbors(CountryNeighborsQuiz),
+    WorldCountries(WorldCountriesQuiz),
 }
 
-#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[allow(dead_code)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum QuizType {
     #[default]
     FlagNameCountry,
+    LocateCountry,
+    CountryCapital,
+    CapitalLocation,
+    CountryFlag,
+    CountryShape,
+    CountryNeighbors,
+    WorldCountries,
+}
+
+impl QuizType {
+    pub const ALL: [Self; 8] = [
+        Self::FlagNameCountry,
+        Self::LocateCountry,
+        Self::CountryCapital,
+        Self::CapitalLocation,
+        Self::CountryFlag,
+        Self::CountryShape,
+        Self::CountryNeighbors,
+        Self::WorldCountries,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::FlagNameCountry => "Flag ➡ Country",
+            Self::LocateCountry => "Country ➡ Map",
+            Self::CountryCapital => "Country ➡ Capital",
+            Self::CapitalLocation => "Capital ➡ Map",
+            Self::CountryFlag => "Country ➡ Flag",
+            Self::CountryShape => "Shape ➡ Country",
+            Self::CountryNeighbors => "Country ➡ Neighbors",
+            Self::WorldCountries => "Name all countries",
+        }
+    }
 }
 
 impl Default for Quiz {
@@ -39,6 +93,13 @@ impl Default for Quiz {
 #[derive(Debug, Serialize, Deserialize)]
 pub enum QuizState {
     FlagNameCountry(FlagNameCountryQuizState),
+    LocateCountry(LocateCountryQuizState),
+    CountryCapital(CountryCapitalQuizState),
+    CapitalLocation(CapitalLocationQuizState),
+    CountryFlag(CountryFlagQuizState),
+    CountryShape(CountryShapeQuizState),
+    CountryNeighbors(CountryNeighborsQuizState),
+    WorldCountries(WorldCountriesQuizState),
 }
 
 impl Default for QuizState {
@@ -53,6 +114,13 @@ impl PersistentObject for Quiz {
     fn save_state(&self) -> Self::PersistentState {
         match self {
             Self::FlagNameCountry(quiz) => QuizState::FlagNameCountry(quiz.save_state()),
+            Self::LocateCountry(quiz) => QuizState::LocateCountry(quiz.save_state()),
+            Self::CountryCapital(quiz) => QuizState::CountryCapital(quiz.save_state()),
+            Self::CapitalLocation(quiz) => QuizState::CapitalLocation(quiz.save_state()),
+            Self::CountryFlag(quiz) => QuizState::CountryFlag(quiz.save_state()),
+            Self::CountryShape(quiz) => QuizState::CountryShape(quiz.save_state()),
+            Self::CountryNeighbors(quiz) => QuizState::CountryNeighbors(quiz.save_state()),
+            Self::WorldCountries(quiz) => QuizState::WorldCountries(quiz.save_state()),
         }
     }
 
@@ -61,54 +129,118 @@ impl PersistentObject for Quiz {
             QuizState::FlagNameCountry(state) => {
                 Self::FlagNameCountry(FlagNameCountryQuiz::load_state(state))
             }
+            QuizState::LocateCountry(state) => {
+                Self::LocateCountry(LocateCountryQuiz::load_state(state))
+            }
+            QuizState::CountryCapital(state) => {
+                Self::CountryCapital(CountryCapitalQuiz::load_state(state))
+            }
+            QuizState::CapitalLocation(state) => {
+                Self::CapitalLocation(CapitalLocationQuiz::load_state(state))
+            }
+            QuizState::CountryFlag(state) => Self::CountryFlag(CountryFlagQuiz::load_state(state)),
+            QuizState::CountryShape(state) => {
+                Self::CountryShape(CountryShapeQuiz::load_state(state))
+            }
+            QuizState::CountryNeighbors(state) => {
+                Self::CountryNeighbors(CountryNeighborsQuiz::load_state(state))
+            }
+            QuizState::WorldCountries(state) => {
+                Self::WorldCountries(WorldCountriesQuiz::load_state(state))
+            }
         }
     }
 }
 
 impl QuizTrait for Quiz {
     fn render(&mut self, ui: &mut Ui) -> Option<bool> {
-        if !self.has_started() {
-            self.start();
-        }
-
         match self {
             Self::FlagNameCountry(quiz) => quiz.render(ui),
+            Self::LocateCountry(quiz) => quiz.render(ui),
+            Self::CountryCapital(quiz) => quiz.render(ui),
+            Self::CapitalLocation(quiz) => quiz.render(ui),
+            Self::CountryFlag(quiz) => quiz.render(ui),
+            Self::CountryShape(quiz) => quiz.render(ui),
+            Self::CountryNeighbors(quiz) => quiz.render(ui),
+            Self::WorldCountries(quiz) => quiz.render(ui),
         }
     }
 
-    fn start(&mut self) {
+    fn start(&mut self, scheduler: &ReviewScheduler) {
         match self {
-            Self::FlagNameCountry(quiz) => quiz.start(),
+            Self::FlagNameCountry(quiz) => quiz.start(scheduler),
+            Self::LocateCountry(quiz) => quiz.start(scheduler),
+            Self::CountryCapital(quiz) => quiz.start(scheduler),
+            Self::CapitalLocation(quiz) => quiz.start(scheduler),
+            Self::CountryFlag(quiz) => quiz.start(scheduler),
+            Self::CountryShape(quiz) => quiz.start(scheduler),
+            Self::CountryNeighbors(quiz) => quiz.start(scheduler),
+            Self::WorldCountries(quiz) => quiz.start(scheduler),
         }
     }
 
     fn finish(&mut self, success: bool) {
         match self {
             Self::FlagNameCountry(quiz) => quiz.finish(success),
+            Self::LocateCountry(quiz) => quiz.finish(success),
+            Self::CountryCapital(quiz) => quiz.finish(success),
+            Self::CapitalLocation(quiz) => quiz.finish(success),
+            Self::CountryFlag(quiz) => quiz.finish(success),
+            Self::CountryShape(quiz) => quiz.finish(success),
+            Self::CountryNeighbors(quiz) => quiz.finish(success),
+            Self::WorldCountries(quiz) => quiz.finish(success),
         }
     }
 
     fn has_started(&self) -> bool {
         match self {
             Self::FlagNameCountry(quiz) => quiz.has_started(),
+            Self::LocateCountry(quiz) => quiz.has_started(),
+            Self::CountryCapital(quiz) => quiz.has_started(),
+            Self::CapitalLocation(quiz) => quiz.has_started(),
+            Self::CountryFlag(quiz) => quiz.has_started(),
+            Self::CountryShape(quiz) => quiz.has_started(),
+            Self::CountryNeighbors(quiz) => quiz.has_started(),
+            Self::WorldCountries(quiz) => quiz.has_started(),
         }
     }
 
     fn is_successful(&self) -> Option<bool> {
         match self {
             Self::FlagNameCountry(quiz) => quiz.is_successful(),
+            Self::LocateCountry(quiz) => quiz.is_successful(),
+            Self::CountryCapital(quiz) => quiz.is_successful(),
+            Self::CapitalLocation(quiz) => quiz.is_successful(),
+            Self::CountryFlag(quiz) => quiz.is_successful(),
+            Self::CountryShape(quiz) => quiz.is_successful(),
+            Self::CountryNeighbors(quiz) => quiz.is_successful(),
+            Self::WorldCountries(quiz) => quiz.is_successful(),
         }
     }
 
     fn reset(&mut self) {
         match self {
             Self::FlagNameCountry(quiz) => quiz.reset(),
+            Self::LocateCountry(quiz) => quiz.reset(),
+            Self::CountryCapital(quiz) => quiz.reset(),
+            Self::CapitalLocation(quiz) => quiz.reset(),
+            Self::CountryFlag(quiz) => quiz.reset(),
+            Self::CountryShape(quiz) => quiz.reset(),
+            Self::CountryNeighbors(quiz) => quiz.reset(),
+            Self::WorldCountries(quiz) => quiz.reset(),
         }
     }
 
     fn collect_stats(&self) -> Option<QuizStats> {
         match self {
             Self::FlagNameCountry(quiz) => quiz.collect_stats(),
+            Self::LocateCountry(quiz) => quiz.collect_stats(),
+            Self::CountryCapital(quiz) => quiz.collect_stats(),
+            Self::CapitalLocation(quiz) => quiz.collect_stats(),
+            Self::CountryFlag(quiz) => quiz.collect_stats(),
+            Self::CountryShape(quiz) => quiz.collect_stats(),
+            Self::CountryNeighbors(quiz) => quiz.collect_stats(),
+            Self::WorldCountries(quiz) => quiz.collect_stats(),
         }
     }
 }
@@ -0,0 +1,419 @@
+use crate::app::components::settings_slider::SettingsSlider;
+use crate::get_data;
+use egui::Ui;
+use icu_normalizer::DecomposingNormalizer;
+use icu_properties::{maps, GeneralCategory};
+use serde::{Deserialize, Serialize};
+
+const LEADING_ARTICLES: &[&str] = &["the "];
+
+/// Alternative country names by ISO code, in addition to the common name.
+const COUNTRY_ALIASES: &[(&str, &[&str])] = &[
+    ("AE", &["UAE", "Emirates"]),
+    ("BA", &["Bosnia"]),
+    (
+        "CD",
+        &["DRC", "Congo-Kinshasa", "Democratic Republic of Congo"],
+    ),
+    ("CG", &["Congo-Brazzaville"]),
+    ("CI", &["Côte d'Ivoire", "Cote d'Ivoire"]),
+    ("CV", &["Cabo Verde"]),
+    ("CZ", &["Czech Republic"]),
+    ("FM", &["Federated States of Micronesia"]),
+    ("GB", &["UK", "Great Britain", "Britain"]),
+    ("GS", &["South Georgia and the South Sandwich Islands"]),
+    ("KP", &["DPRK"]),
+    ("MK", &["Macedonia"]),
+    ("MM", &["Burma"]),
+    ("NL", &["Holland"]),
+    ("PS", &["Palestinian Territories"]),
+    ("SZ", &["Swaziland"]),
+    ("TL", &["East Timor"]),
+    ("TR", &["Türkiye"]),
+    ("US", &["USA"]),
+    ("VA", &["Vatican", "Holy See"]),
+    ("VC", &["St Vincent"]),
+];
+
+/// Alternative capital names, in addition to the name in the data set.
+const CAPITAL_ALIASES: &[(&str, &[&str])] = &[
+    ("City of Victoria", &["Victoria"]),
+    ("Jamestown (Saint Helena)", &["Jamestown"]),
+    ("Kyiv", &["Kiev"]),
+    ("Naypyidaw", &["Nay Pyi Taw"]),
+    ("Ulan Bator", &["Ulaanbaatar"]),
+    ("Washington, D.C.", &["Washington"]),
+];
+
+/// How forgiving typed answers are matched against the solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnswerMatching {
+    pub accept_official_names: bool,
+    pub accept_iso_codes: bool,
+    /// Maximum amount of typos (edits) a fuzzy match may contain.
+    pub typo_tolerance: u8,
+}
+
+impl Default for AnswerMatching {
+    fn default() -> Self {
+        Self {
+            accept_official_names: true,
+            accept_iso_codes: false,
+            typo_tolerance: 1,
+        }
+    }
+}
+
+impl AnswerMatching {
+    pub fn render(&mut self, ui: &mut Ui) {
+        SettingsSlider::build()
+            .text("Typo tolerance")
+            .tooltip("How many typos an answer may contain, short names always have to be exact.")
+            .draw(ui, &mut self.typo_tolerance, 1, 0..=3, 1.0);
+
+        ui.checkbox(&mut self.accept_official_names, "Accept official names")
+            .on_hover_text("E.g. \"Federal Republic of Germany\" for Germany.");
+        ui.checkbox(&mut self.accept_iso_codes, "Accept ISO codes")
+            .on_hover_text("E.g. \"DE\" or \"DEU\" for Germany.");
+    }
+}
+
+/// How a typed answer matched the solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnswerMatch {
+    Exact,
+    Fuzzy,
+}
+
+impl AnswerMatch {
+    /// Combines the matches of several answers, one fuzzy answer makes the whole fuzzy.
+    pub fn combine(first: Option<Self>, second: Self) -> Self {
+        match (first, second) {
+            (Some(Self::Fuzzy), _) | (_, Self::Fuzzy) => Self::Fuzzy,
+            _ => Self::Exact,
+        }
+    }
+}
+
+/// Whether a typed answer names the given country.
+pub fn match_country_name(
+    answer: &str,
+    country_code: &str,
+    matching: &AnswerMatching,
+) -> Option<AnswerMatch> {
+    match_country_among(answer, &[country_code.to_string()], matching).map(|(_, matched)| matched)
+}
+
+/// Finds the country out of `country_codes` the typed answer names.
+///
+/// Exact matches win over fuzzy ones and an answer exactly naming any other country is never
+/// fuzzily matched, so typing "Iraq" won't count as "Iran".
+pub fn match_country_among(
+    answer: &str,
+    country_codes: &[String],
+    matching: &AnswerMatching,
+) -> Option<(String, AnswerMatch)> {
+    let candidates = country_codes
+        .iter()
+        .map(|code| (code.clone(), country_names(code, matching)));
+    let all_names = || {
+        get_data()
+            .get_country_codes()
+            .iter()
+            .flat_map(|code| country_names(code, matching))
+            .collect()
+    };
+
+    match_among(answer, candidates, all_names, matching.typo_tolerance)
+}
+
+/// Finds the capital out of `capital_names` the typed answer names, see [`match_country_among`].
+pub fn match_capital_among(
+    answer: &str,
+    capital_names: &[String],
+    matching: &AnswerMatching,
+) -> Option<(String, AnswerMatch)> {
+    let candidates = capital_names
+        .iter()
+        .map(|name| (name.clone(), capital_aliases(name)));
+    let all_names = || {
+        get_data()
+            .countries_iter()
+            .flat_map(|country| country.capitals.iter())
+            .flat_map(|name| capital_aliases(name))
+            .collect()
+    };
+
+    match_among(answer, candidates, all_names, matching.typo_tolerance)
+}
+
+/// Lowercases the text, strips diacritics, punctuation and leading articles.
+pub fn normalize(text: &str) -> String {
+    // Decomposing splits accented letters into the base letter and its combining marks.
+    let decomposed = DecomposingNormalizer::new_nfd().normalize(text);
+    let general_category = maps::general_category();
+
+    let mut folded = String::with_capacity(decomposed.len());
+    for char in decomposed.chars().flat_map(char::to_lowercase) {
+        match char {
+            '\'' | '’' | '`' | '.' => {}
+            '&' => folded.push_str(" and "),
+            char if general_category.get(char) == GeneralCategory::NonspacingMark => {}
+            char if char.is_alphanumeric() => match fold_letter(char) {
+                Some(replacement) => folded.push_str(replacement),
+                None => folded.push(char),
+            },
+            _ => folded.push(' '),
+        }
+    }
+
+    let mut normalized = folded
+        .split_whitespace()
+        .map(|word| if word == "st" { "saint" } else { word })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    for article in LEADING_ARTICLES {
+        if let Some(stripped) = normalized.strip_prefix(article) {
+            normalized = stripped.to_string();
+        }
+    }
+
+    normalized
+}
+
+fn match_among(
+    answer: &str,
+    candidates: impl Iterator<Item = (String, Vec<String>)>,
+    all_names: impl FnOnce() -> Vec<String>,
+    typo_tolerance: u8,
+) -> Option<(String, AnswerMatch)> {
+    let answer = normalize(answer);
+    if answer.is_empty() {
+        return None;
+    }
+
+    let candidates: Vec<(String, Vec<String>)> = candidates.collect();
+    if let Some((key, _)) = candidates.iter().find(|(_, names)| names.contains(&answer)) {
+        return Some((key.clone(), AnswerMatch::Exact));
+    }
+
+    if typo_tolerance == 0 || all_names().contains(&answer) {
+        return None;
+    }
+
+    candidates
+        .into_iter()
+        .filter_map(|(key, names)| {
+            names
+                .iter()
+                .filter_map(|name| typo_distance(&answer, name, typo_tolerance))
+                .min()
+                .map(|distance| (key, distance))
+        })
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(key, _)| (key, AnswerMatch::Fuzzy))
+}
+
+/// Edit distance between answer and name, if it is within the tolerance.
+/// Short names allow fewer typos, so "Chad" can't be reached from "Chat".
+fn typo_distance(answer: &str, name: &str, typo_tolerance: u8) -> Option<usize> {
+    let allowed = (typo_tolerance as usize).min(name.chars().count() / 5);
+    if allowed == 0 {
+        return None;
+    }
+
+    let distance = levenshtein(answer, name);
+    (distance <= allowed).then_some(distance)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+fn country_names(country_code: &str, matching: &AnswerMatching) -> Vec<String> {
+    let data = get_data();
+    let Some(country) = data.get_country(country_code) else {
+        return Vec::new();
+    };
+
+    let mut names = vec![normalize(&country.common_name)];
+    if let Some((_, aliases)) = COUNTRY_ALIASES
+        .iter()
+        .find(|(code, _)| *code == country.iso_a2)
+    {
+        names.extend(aliases.iter().map(|alias| normalize(alias)));
+    }
+    if matching.accept_official_names {
+        names.push(normalize(&country.official_name));
+    }
+    if matching.accept_iso_codes {
+        names.push(normalize(&country.iso_a2));
+        names.push(normalize(&country.iso_a3));
+    }
+
+    names
+}
+
+fn capital_aliases(capital_name: &str) -> Vec<String> {
+    let mut names = vec![normalize(capital_name)];
+    if let Some((_, aliases)) = CAPITAL_ALIASES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(capital_name))
+    {
+        names.extend(aliases.iter().map(|alias| normalize(alias)));
+    }
+    names
+}
+
+/// Ligatures and letters with a stroke, which don't decompose into a base letter and a mark.
+fn fold_letter(char: char) -> Option<&'static str> {
+    let replacement = match char {
+        'æ' => "ae",
+        'đ' | 'ð' => "d",
+        'ħ' => "h",
+        'ı' => "i",
+        'ł' => "l",
+        'ø' => "o",
+        'œ' => "oe",
+        'ß' => "ss",
+        'þ' => "th",
+        _ => return None,
+    };
+    Some(replacement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(key: &str, names: &[&str]) -> impl Iterator<Item = (String, Vec<String>)> {
+        let names = names.iter().map(|name| name.to_string()).collect();
+        std::iter::once((key.to_string(), names))
+    }
+
+    #[test]
+    fn normalizes_articles_and_punctuation() {
+        assert_eq!(normalize("The Gambia"), "gambia");
+        assert_eq!(normalize("  the   Bahamas "), "bahamas");
+        assert_eq!(normalize("Guinea-Bissau"), "guinea bissau");
+        assert_eq!(normalize("St. Lucia"), "saint lucia");
+        assert_eq!(normalize("Trinidad & Tobago"), "trinidad and tobago");
+        assert_eq!(normalize("Washington, D.C."), "washington dc");
+    }
+
+    #[test]
+    fn normalizes_diacritics() {
+        assert_eq!(normalize("Côte d'Ivoire"), "cote divoire");
+        // The same name with a combining circumflex instead of a precomposed letter.
+        assert_eq!(normalize("Co\u{0302}te d’Ivoire"), "cote divoire");
+        assert_eq!(normalize("São Tomé and Príncipe"), "sao tome and principe");
+        assert_eq!(normalize("Việt Nam"), "viet nam");
+        assert_eq!(normalize("Reykjavík"), "reykjavik");
+        assert_eq!(normalize("Łódź"), "lodz");
+        assert_eq!(normalize("Straße"), "strasse");
+        assert_eq!(normalize("Færøerne"), "faeroerne");
+        assert_eq!(normalize("Þórshöfn"), "thorshofn");
+    }
+
+    #[test]
+    fn computes_levenshtein_distance() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "chad"), 4);
+        assert_eq!(levenshtein("chad", ""), 4);
+        assert_eq!(levenshtein("germany", "germany"), 0);
+        assert_eq!(levenshtein("germny", "germany"), 1);
+        assert_eq!(levenshtein("peru", "pérú"), 2);
+    }
+
+    #[test]
+    fn limits_typos_in_short_names() {
+        assert_eq!(typo_distance("chat", "chad", 3), None);
+        assert_eq!(typo_distance("germny", "germany", 3), Some(1));
+        assert_eq!(typo_distance("germnay", "germany", 3), None);
+        assert_eq!(typo_distance("germny", "germany", 0), None);
+        assert_eq!(typo_distance("mauritanai", "mauritania", 2), Some(2));
+    }
+
+    #[test]
+    fn exact_name_of_other_country_blocks_fuzzy_match() {
+        let all_names = || vec!["gambia".to_string(), "zambia".to_string()];
+        assert_eq!(
+            match_among("Zambia", candidates("GM", &["gambia"]), all_names, 1),
+            None
+        );
+        assert_eq!(
+            match_among("Gambai", candidates("GM", &["gambia"]), all_names, 1),
+            None
+        );
+        assert_eq!(
+            match_among("Gammbia", candidates("GM", &["gambia"]), all_names, 1),
+            Some(("GM".to_string(), AnswerMatch::Fuzzy))
+        );
+
+        let matching = AnswerMatching {
+            typo_tolerance: 3,
+            ..AnswerMatching::default()
+        };
+        assert_eq!(match_country_name("Iraq", "IR", &matching), None);
+        assert_eq!(
+            match_country_name("Iran", "IR", &matching),
+            Some(AnswerMatch::Exact)
+        );
+        assert_eq!(
+            match_country_among("Iraq", &["IR".to_string(), "IQ".to_string()], &matching),
+            Some(("IQ".to_string(), AnswerMatch::Exact))
+        );
+    }
+
+    #[test]
+    fn accepts_iso_codes_only_when_enabled() {
+        let matching = AnswerMatching::default();
+        assert_eq!(match_country_name("DE", "DE", &matching), None);
+        assert_eq!(match_country_name("DEU", "DE", &matching), None);
+
+        let matching = AnswerMatching {
+            accept_iso_codes: true,
+            ..matching
+        };
+        assert_eq!(
+            match_country_name("de", "DE", &matching),
+            Some(AnswerMatch::Exact)
+        );
+        assert_eq!(
+            match_country_name("DEU", "DE", &matching),
+            Some(AnswerMatch::Exact)
+        );
+    }
+
+    #[test]
+    fn ambiguous_names_match_neither_country() {
+        let matching = AnswerMatching::default();
+        for code in ["CG", "CD"] {
+            assert_eq!(match_country_name("Congo", code, &matching), None);
+        }
+        for code in ["KR", "KP"] {
+            assert_eq!(match_country_name("Korea", code, &matching), None);
+        }
+        assert_eq!(match_country_name("England", "GB", &matching), None);
+        assert_eq!(match_country_name("America", "US", &matching), None);
+        assert_eq!(
+            match_country_name("Congo-Brazzaville", "CG", &matching),
+            Some(AnswerMatch::Exact)
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use crate::data::flag_colors::{FlagColor, FlagColorShare};
+use crate::get_data;
+use rand::seq::{IndexedRandom, SliceRandom};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// How many of the most similar countries are considered when picking biased distractors.
+const CANDIDATE_POOL_FACTOR: usize = 3;
+/// Colours covering less of a flag than this are details like emblems or the blended pixels
+/// along stripe edges, not the flag's colours.
+const MIN_COLOR_SHARE: f32 = 0.08;
+/// Flags are compared by at most this many of their largest colours.
+const DOMINANT_COLORS: usize = 4;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DistractorBias {
+    #[default]
+    Random,
+    SimilarFlags,
+    Nearby,
+}
+
+impl DistractorBias {
+    pub const ALL: [Self; 3] = [Self::Random, Self::SimilarFlags, Self::Nearby];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Random => "Random",
+            Self::SimilarFlags => "Similar flags",
+            Self::Nearby => "Nearby countries",
+        }
+    }
+}
+
+/// Picks `count` country codes out of `pool`, other than `solution`, to be used as wrong answers.
+pub fn pick_distractors(
+    solution: &str,
+    pool: &[String],
+    count: usize,
+    bias: DistractorBias,
+) -> Vec<String> {
+    let data = get_data();
+    let mut rng = rand::rng();
+
+    let mut candidates: Vec<&String> = pool
+        .iter()
+        .filter(|code| code.as_str() != solution)
+        .collect();
+
+    match bias {
+        DistractorBias::Random => {}
+        DistractorBias::SimilarFlags => {
+            let colors = dominant_colors(data.get_flag_colors(solution));
+            candidates.shuffle(&mut rng);
+
+            let mut scored: Vec<(f32, &String)> = candidates
+                .into_iter()
+                .map(|code| {
+                    let similarity =
+                        color_similarity(&colors, &dominant_colors(data.get_flag_colors(code)));
+                    (similarity, code)
+                })
+                .collect();
+            scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+            candidates = scored.into_iter().map(|(_, code)| code).collect();
+            candidates.truncate(count * CANDIDATE_POOL_FACTOR);
+        }
+        DistractorBias::Nearby => {
+            if let Some(center) = data.get_country_bounds(solution).map(|b| b.center()) {
+                candidates.sort_by(|a, b| {
+                    let distance_a = country_distance(center, a);
+                    let distance_b = country_distance(center, b);
+                    distance_a
+                        .partial_cmp(&distance_b)
+                        .unwrap_or(Ordering::Equal)
+                });
+                candidates.truncate(count * CANDIDATE_POOL_FACTOR);
+            }
+        }
+    }
+
+    candidates
+        .choose_multiple(&mut rng, count)
+        .map(|code| code.to_string())
+        .collect()
+}
+
+/// The largest colours of a flag, sorted for [`color_similarity`].
+fn dominant_colors(colors: &[FlagColorShare]) -> Vec<FlagColor> {
+    let mut dominant: Vec<FlagColor> = colors
+        .iter()
+        .filter(|color| color.share >= MIN_COLOR_SHARE)
+        .take(DOMINANT_COLORS)
+        .map(|color| color.color)
+        .collect();
+    dominant.sort();
+    dominant
+}
+
+/// Jaccard similarity of two sorted colour sets.
+fn color_similarity<T: Ord>(a: &[T], b: &[T]) -> f32 {
+    let shared = a
+        .iter()
+        .filter(|color| b.binary_search(color).is_ok())
+        .count();
+    let total = a.len() + b.len() - shared;
+    if total == 0 {
+        0.0
+    } else {
+        shared as f32 / total as f32
+    }
+}
+
+fn country_distance(center: egui::Pos2, country_code: &str) -> f32 {
+    get_data()
+        .get_country_bounds(country_code)
+        .map(|bounds| bounds.center().distance(center))
+        .unwrap_or(f32::INFINITY)
+}
@@ -0,0 +1,112 @@
+use crate::get_data;
+use egui::Ui;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Restricts a quiz to countries of the selected subregions, nothing selected means the whole world.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionFilter {
+    subregions: BTreeSet<String>,
+}
+
+impl RegionFilter {
+    pub fn is_whole_world(&self) -> bool {
+        self.subregions.is_empty()
+    }
+
+    pub fn contains(&self, country_code: &str) -> bool {
+        self.is_whole_world()
+            || get_data()
+                .get_country_subregion(country_code)
+                .is_some_and(|subregion| self.subregions.contains(subregion))
+    }
+
+    /// Codes of all countries passing the filter, falls back to all countries if none do.
+    pub fn country_codes(&self) -> Vec<String> {
+        self.candidates(|_| true)
+    }
+
+    /// Codes of the countries passing the filter a quiz can ask about, e.g. the ones with
+    /// neighbours. Falls back to all such countries of the world if none pass the filter.
+    pub fn candidates(&self, has_solution: impl Fn(&str) -> bool) -> Vec<String> {
+        let all_codes: Vec<String> = get_data()
+            .get_country_codes()
+            .iter()
+            .filter(|code| has_solution(code))
+            .cloned()
+            .collect();
+        let filtered: Vec<String> = all_codes
+            .iter()
+            .filter(|code| self.contains(code))
+            .cloned()
+            .collect();
+
+        if filtered.is_empty() {
+            all_codes
+        } else {
+            filtered
+        }
+    }
+
+    pub fn render(&mut self, ui: &mut Ui) {
+        let header = if self.is_whole_world() {
+            "Regions: whole world".to_string()
+        } else {
+            format!("Regions: {} selected", self.subregions.len())
+        };
+
+        egui::CollapsingHeader::new(header)
+            .id_salt("region_filter")
+            .show(ui, |ui| {
+                if ui
+                    .add_enabled(!self.is_whole_world(), egui::Button::new("Whole world"))
+                    .clicked()
+                {
+                    self.subregions.clear();
+                }
+
+                for (continent, subregions) in get_data().get_continents() {
+                    self.render_continent(ui, continent, subregions);
+                }
+            });
+    }
+
+    fn render_continent(&mut self, ui: &mut Ui, continent: &str, subregions: &[String]) {
+        let selected_count = subregions
+            .iter()
+            .filter(|subregion| self.subregions.contains(*subregion))
+            .count();
+        let mut all_selected = selected_count == subregions.len();
+        let partially_selected = selected_count > 0 && !all_selected;
+
+        let continent_checkbox =
+            egui::Checkbox::new(&mut all_selected, continent).indeterminate(partially_selected);
+        if ui.add(continent_checkbox).changed() {
+            for subregion in subregions {
+                self.set_selected(subregion, all_selected);
+            }
+        }
+
+        // A continent without subregions is fully described by its own checkbox.
+        if subregions.len() == 1 && subregions[0] == continent {
+            return;
+        }
+
+        ui.indent(continent, |ui| {
+            for subregion in subregions {
+                let mut selected = self.subregions.contains(subregion);
+                if ui.checkbox(&mut selected, subregion).changed() {
+                    self.set_selected(subregion, selected);
+                }
+            }
+        });
+    }
+
+    fn set_selected(&mut self, subregion: &str, selected: bool) {
+        if selected {
+            self.subregions.insert(subregion.to_string());
+        } else {
+            self.subregions.remove(subregion);
+        }
+    }
+}
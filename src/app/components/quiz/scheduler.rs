@@ -0,0 +1,293 @@
+use crate::app::components::quiz::stats::{QuizStats, QuizStatsTrait};
+use crate::app::components::quiz::QuizType;
+use crate::utils::time::timestamp_ms;
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const DAY_MS: u128 = 24 * 60 * 60 * 1000;
+/// Items answered wrong come back after a short break instead of a full day.
+const RELEARN_DELAY_MS: u128 = 10 * 60 * 1000;
+const INITIAL_EASE: f32 = 2.5;
+const MIN_EASE: f32 = 1.3;
+/// SM-2 answer grades, 0 (blackout) to 5 (perfect).
+const GRADE_CORRECT: f32 = 4.0;
+const GRADE_WRONG: f32 = 1.0;
+
+/// Spaced repetition state of a single quiz subject, following the SM-2 algorithm.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemMemory {
+    pub repetitions: u32,
+    pub ease: f32,
+    pub interval_days: f32,
+    pub last_review_ms: u128,
+    pub due_at_ms: u128,
+}
+
+impl Default for ItemMemory {
+    fn default() -> Self {
+        Self {
+            repetitions: 0,
+            ease: INITIAL_EASE,
+            interval_days: 0.0,
+            last_review_ms: 0,
+            due_at_ms: 0,
+        }
+    }
+}
+
+impl ItemMemory {
+    fn review(&mut self, success: bool, reviewed_at_ms: u128) {
+        let grade = if success { GRADE_CORRECT } else { GRADE_WRONG };
+        self.ease = (self.ease + 0.1 - (5.0 - grade) * (0.08 + (5.0 - grade) * 0.02)).max(MIN_EASE);
+        self.last_review_ms = reviewed_at_ms;
+
+        if !success {
+            self.repetitions = 0;
+            self.interval_days = 0.0;
+            self.due_at_ms = reviewed_at_ms + RELEARN_DELAY_MS;
+            return;
+        }
+
+        self.repetitions += 1;
+        self.interval_days = match self.repetitions {
+            1 => 1.0,
+            2 => 6.0,
+            _ => self.interval_days * self.ease,
+        };
+        self.due_at_ms = reviewed_at_ms + (self.interval_days as f64 * DAY_MS as f64) as u128;
+    }
+
+    fn is_due(&self, now_ms: u128) -> bool {
+        self.due_at_ms <= now_ms
+    }
+}
+
+/// Decides which subject (country code, capital name, ...) a quiz should ask next.
+///
+/// Overdue subjects come first, then ones that were never asked, then the ones due the soonest.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ReviewScheduler {
+    items: HashMap<QuizType, HashMap<String, ItemMemory>>,
+}
+
+impl ReviewScheduler {
+    /// Replays the quiz history, used for saves from before the scheduler existed.
+    pub fn from_history(history: &[QuizStats]) -> Self {
+        let mut scheduler = Self::default();
+        history.iter().for_each(|stats| scheduler.record(stats));
+        scheduler
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn record(&mut self, stats: &QuizStats) {
+        let common = stats.get_common();
+        let (Some(subject), Some(success)) = (&common.subject, common.success) else {
+            return;
+        };
+
+        self.items
+            .entry(stats.quiz_type())
+            .or_default()
+            .entry(subject.clone())
+            .or_default()
+            .review(success, common.finished_at_ms);
+    }
+
+    pub fn get_memory(&self, quiz_type: QuizType, subject: &str) -> Option<&ItemMemory> {
+        self.items.get(&quiz_type)?.get(subject)
+    }
+
+    pub fn pick(&self, quiz_type: QuizType, candidates: &[String]) -> Option<String> {
+        let mut rng = rand::rng();
+        let now_ms = timestamp_ms();
+
+        let (seen, unseen): (Vec<&String>, Vec<&String>) = candidates
+            .iter()
+            .partition(|subject| self.get_memory(quiz_type, subject).is_some());
+        let due_at = |subject: &&String| {
+            self.get_memory(quiz_type, subject)
+                .map(|memory| memory.due_at_ms)
+                .unwrap_or_default()
+        };
+
+        let most_overdue = seen
+            .iter()
+            .filter(|subject| {
+                self.get_memory(quiz_type, subject)
+                    .is_some_and(|memory| memory.is_due(now_ms))
+            })
+            .min_by_key(|subject| due_at(subject));
+
+        most_overdue
+            .or_else(|| unseen.choose(&mut rng))
+            .or_else(|| seen.iter().min_by_key(|subject| due_at(subject)))
+            .map(|subject| subject.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::components::quiz::stats::country_shape::CountryShapeQuizStats;
+    use crate::app::components::quiz::stats::QuizStatsCommon;
+
+    fn shape_quiz(subject: &str, success: bool, finished_at_ms: u128) -> QuizStats {
+        QuizStats::CountryShape(CountryShapeQuizStats {
+            common: QuizStatsCommon {
+                started_at_ms: finished_at_ms - 1000,
+                finished_at_ms,
+                subject: Some(subject.to_string()),
+                success: Some(success),
+                attempts: 1,
+                answers: Vec::new(),
+            },
+            answer_match: None,
+            assisted: false,
+        })
+    }
+
+    fn memory_due_at(due_at_ms: u128) -> ItemMemory {
+        ItemMemory {
+            repetitions: 1,
+            interval_days: 1.0,
+            due_at_ms,
+            ..ItemMemory::default()
+        }
+    }
+
+    fn scheduler_with(items: &[(&str, ItemMemory)]) -> ReviewScheduler {
+        let items = items
+            .iter()
+            .map(|(subject, memory)| (subject.to_string(), memory.clone()))
+            .collect();
+        ReviewScheduler {
+            items: HashMap::from([(QuizType::CountryShape, items)]),
+        }
+    }
+
+    fn subjects(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn grows_interval_on_correct_answers() {
+        let mut memory = ItemMemory::default();
+
+        memory.review(true, 0);
+        assert_eq!(memory.repetitions, 1);
+        assert_eq!(memory.interval_days, 1.0);
+        assert_eq!(memory.due_at_ms, DAY_MS);
+        // A correct answer (grade 4) keeps the ease as it is.
+        assert_eq!(memory.ease, INITIAL_EASE);
+
+        memory.review(true, DAY_MS);
+        assert_eq!(memory.interval_days, 6.0);
+        assert_eq!(memory.due_at_ms, 7 * DAY_MS);
+
+        memory.review(true, 7 * DAY_MS);
+        assert_eq!(memory.repetitions, 3);
+        assert_eq!(memory.interval_days, 6.0 * INITIAL_EASE);
+        assert_eq!(memory.due_at_ms, 22 * DAY_MS);
+    }
+
+    #[test]
+    fn relearns_wrong_answers_after_short_delay() {
+        let mut memory = ItemMemory::default();
+        memory.review(true, 0);
+        memory.review(true, DAY_MS);
+
+        memory.review(false, 7 * DAY_MS);
+        assert_eq!(memory.repetitions, 0);
+        assert_eq!(memory.interval_days, 0.0);
+        assert_eq!(memory.last_review_ms, 7 * DAY_MS);
+        assert_eq!(memory.due_at_ms, 7 * DAY_MS + RELEARN_DELAY_MS);
+        assert!((memory.ease - 1.96).abs() < 1e-6);
+        assert!(!memory.is_due(7 * DAY_MS + RELEARN_DELAY_MS - 1));
+        assert!(memory.is_due(7 * DAY_MS + RELEARN_DELAY_MS));
+
+        for _ in 0..5 {
+            memory.review(false, 7 * DAY_MS);
+        }
+        assert_eq!(memory.ease, MIN_EASE);
+
+        // Relearning starts over with the short intervals.
+        memory.review(true, 8 * DAY_MS);
+        assert_eq!(memory.repetitions, 1);
+        assert_eq!(memory.due_at_ms, 9 * DAY_MS);
+    }
+
+    #[test]
+    fn picks_most_overdue_first() {
+        let now_ms = timestamp_ms();
+        let scheduler = scheduler_with(&[
+            ("FR", memory_due_at(now_ms - DAY_MS)),
+            ("DE", memory_due_at(now_ms - 2 * DAY_MS)),
+            ("IT", memory_due_at(now_ms + DAY_MS)),
+        ]);
+
+        let picked = scheduler.pick(QuizType::CountryShape, &subjects(&["FR", "DE", "IT", "ES"]));
+        assert_eq!(picked.as_deref(), Some("DE"));
+    }
+
+    #[test]
+    fn picks_unseen_before_not_yet_due() {
+        let now_ms = timestamp_ms();
+        let scheduler = scheduler_with(&[
+            ("FR", memory_due_at(now_ms + DAY_MS)),
+            ("DE", memory_due_at(now_ms + 2 * DAY_MS)),
+        ]);
+
+        let candidates = subjects(&["FR", "DE", "ES", "PT"]);
+        for _ in 0..10 {
+            let picked = scheduler.pick(QuizType::CountryShape, &candidates).unwrap();
+            assert!(picked == "ES" || picked == "PT", "picked {picked}");
+        }
+    }
+
+    #[test]
+    fn picks_soonest_due_when_all_seen() {
+        let now_ms = timestamp_ms();
+        let scheduler = scheduler_with(&[
+            ("FR", memory_due_at(now_ms + 2 * DAY_MS)),
+            ("DE", memory_due_at(now_ms + DAY_MS)),
+            ("IT", memory_due_at(now_ms + 3 * DAY_MS)),
+        ]);
+
+        let picked = scheduler.pick(QuizType::CountryShape, &subjects(&["FR", "DE", "IT"]));
+        assert_eq!(picked.as_deref(), Some("DE"));
+        assert_eq!(scheduler.pick(QuizType::CountryShape, &[]), None);
+        // Memories of other quiz types don't count.
+        assert!(scheduler
+            .pick(QuizType::LocateCountry, &subjects(&["FR"]))
+            .is_some());
+    }
+
+    #[test]
+    fn replays_history() {
+        let history = [
+            shape_quiz("FR", true, DAY_MS),
+            shape_quiz("FR", true, 2 * DAY_MS),
+            shape_quiz("DE", false, 3 * DAY_MS),
+        ];
+        let scheduler = ReviewScheduler::from_history(&history);
+
+        let france = scheduler.get_memory(QuizType::CountryShape, "FR").unwrap();
+        assert_eq!(france.repetitions, 2);
+        assert_eq!(france.due_at_ms, 8 * DAY_MS);
+        let germany = scheduler.get_memory(QuizType::CountryShape, "DE").unwrap();
+        assert_eq!(germany.due_at_ms, 3 * DAY_MS + RELEARN_DELAY_MS);
+        assert!(scheduler
+            .get_memory(QuizType::LocateCountry, "FR")
+            .is_none());
+
+        let mut unfinished = shape_quiz("IT", true, DAY_MS);
+        if let QuizStats::CountryShape(stats) = &mut unfinished {
+            stats.common.success = None;
+        }
+        assert!(ReviewScheduler::from_history(&[unfinished]).is_empty());
+    }
+}
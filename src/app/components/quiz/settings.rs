@@ -1,9 +1,46 @@
-use crate::app::components::quiz::{Quiz, QuizTrait};
+use crate::app::components::quiz::Quiz;
 use egui::Ui;
+use serde::{Deserialize, Serialize};
 
+pub mod capital_location;
+pub mod country_capital;
+pub mod country_flag;
+pub mod country_neighbors;
+pub mod country_shape;
 pub mod flag_name_country;
+pub mod locate_country;
+pub mod world_countries;
 
 pub trait QuizSettingsTrait: Sized {
     fn render(&mut self, ui: &mut Ui);
     fn create_quiz(&self) -> Quiz;
 }
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnswerMode {
+    #[default]
+    FreeText,
+    FourChoices,
+    SixChoices,
+}
+
+impl AnswerMode {
+    pub const ALL: [Self; 3] = [Self::FreeText, Self::FourChoices, Self::SixChoices];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::FreeText => "Free text",
+            Self::FourChoices => "4 choices",
+            Self::SixChoices => "6 choices",
+        }
+    }
+
+    /// The amount of answers to choose from, [`None`] for free text entry.
+    pub fn choice_count(&self) -> Option<usize> {
+        match self {
+            Self::FreeText => None,
+            Self::FourChoices => Some(4),
+            Self::SixChoices => Some(6),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::app::components::quiz::region_filter::RegionFilter;
+use crate::app::components::quiz::settings::QuizSettingsTrait;
+use crate::app::components::quiz::types::capital_location::CapitalLocationQuiz;
+use crate::app::components::quiz::Quiz;
+use crate::app::components::settings_slider::SettingsSlider;
+use egui::Ui;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_PERFECT_DISTANCE_KM: u32 = 100;
+const DEFAULT_CLOSE_DISTANCE_KM: u32 = 500;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapitalLocationQuizSettings {
+    /// Guesses within this distance count as perfect.
+    pub perfect_distance_km: u32,
+    /// Guesses within this distance still count as correct, everything further away is a miss.
+    pub close_distance_km: u32,
+    pub show_country: bool,
+    #[serde(default)]
+    pub region_filter: RegionFilter,
+}
+
+impl Default for CapitalLocationQuizSettings {
+    fn default() -> Self {
+        Self {
+            perfect_distance_km: DEFAULT_PERFECT_DISTANCE_KM,
+            close_distance_km: DEFAULT_CLOSE_DISTANCE_KM,
+            show_country: true,
+            region_filter: RegionFilter::default(),
+        }
+    }
+}
+
+impl QuizSettingsTrait for CapitalLocationQuizSettings {
+    fn render(&mut self, ui: &mut Ui) {
+        SettingsSlider::build()
+            .text("Perfect")
+            .tooltip("Guesses within this distance of the capital count as perfect.")
+            .suffix(" km")
+            .logarithmic(true)
+            .draw(
+                ui,
+                &mut self.perfect_distance_km,
+                DEFAULT_PERFECT_DISTANCE_KM,
+                10..=2000,
+                10.0,
+            );
+        SettingsSlider::build()
+            .text("Close")
+            .tooltip("Guesses within this distance of the capital still count as correct.")
+            .suffix(" km")
+            .logarithmic(true)
+            .draw(
+                ui,
+                &mut self.close_distance_km,
+                DEFAULT_CLOSE_DISTANCE_KM,
+                10..=5000,
+                10.0,
+            );
+        self.close_distance_km = self.close_distance_km.max(self.perfect_distance_km);
+
+        ui.checkbox(&mut self.show_country, "Show country")
+            .on_hover_text("Show which country the capital belongs to.");
+
+        self.region_filter.render(ui);
+    }
+
+    fn create_quiz(&self) -> Quiz {
+        Quiz::CapitalLocation(CapitalLocationQuiz::with_settings(self.clone()))
+    }
+}
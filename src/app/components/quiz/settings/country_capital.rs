@@ -0,0 +1,55 @@
+use crate::app::components::quiz::answer_matching::AnswerMatching;
+use crate::app::components::quiz::region_filter::RegionFilter;
+use crate::app::components::quiz::settings::QuizSettingsTrait;
+use crate::app::components::quiz::types::country_capital::CountryCapitalQuiz;
+use crate::app::components::quiz::Quiz;
+use crate::app::components::settings_slider::SettingsSlider;
+use egui::Ui;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CountryCapitalQuizSettings {
+    pub max_tries: u8,
+    pub show_flag: bool,
+    pub all_capitals_required: bool,
+    #[serde(default)]
+    pub answer_matching: AnswerMatching,
+    #[serde(default)]
+    pub region_filter: RegionFilter,
+}
+
+impl Default for CountryCapitalQuizSettings {
+    fn default() -> Self {
+        Self {
+            max_tries: 1,
+            show_flag: true,
+            all_capitals_required: false,
+            answer_matching: AnswerMatching::default(),
+            region_filter: RegionFilter::default(),
+        }
+    }
+}
+
+impl QuizSettingsTrait for CountryCapitalQuizSettings {
+    fn render(&mut self, ui: &mut Ui) {
+        SettingsSlider::build()
+            .text("Tries")
+            .tooltip("How often you can try guessing the country's capital.")
+            .draw(ui, &mut self.max_tries, 1, 1..=10, 1.0);
+
+        ui.checkbox(&mut self.show_flag, "Show flag as hint")
+            .on_hover_text("Show the country's flag next to its name.");
+        ui.checkbox(&mut self.all_capitals_required, "All capitals required")
+            .on_hover_text(
+                "For countries with several capitals, every one of them has to be named.",
+            );
+
+        self.answer_matching.render(ui);
+
+        self.region_filter.render(ui);
+    }
+
+    fn create_quiz(&self) -> Quiz {
+        Quiz::CountryCapital(CountryCapitalQuiz::with_settings(self.clone()))
+    }
+}
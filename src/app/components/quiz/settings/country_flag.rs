@@ -0,0 +1,49 @@
+use crate::app::components::quiz::region_filter::RegionFilter;
+use crate::app::components::quiz::settings::QuizSettingsTrait;
+use crate::app::components::quiz::types::country_flag::CountryFlagQuiz;
+use crate::app::components::quiz::Quiz;
+use crate::app::components::settings_slider::SettingsSlider;
+use egui::Ui;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CountryFlagQuizSettings {
+    pub max_tries: u8,
+    pub flag_count: u8,
+    pub similar_colors: bool,
+    #[serde(default)]
+    pub region_filter: RegionFilter,
+}
+
+impl Default for CountryFlagQuizSettings {
+    fn default() -> Self {
+        Self {
+            max_tries: 1,
+            flag_count: 6,
+            similar_colors: false,
+            region_filter: RegionFilter::default(),
+        }
+    }
+}
+
+impl QuizSettingsTrait for CountryFlagQuizSettings {
+    fn render(&mut self, ui: &mut Ui) {
+        SettingsSlider::build()
+            .text("Tries")
+            .tooltip("How often you can try picking the country's flag.")
+            .draw(ui, &mut self.max_tries, 1, 1..=10, 1.0);
+        SettingsSlider::build()
+            .text("Flags")
+            .tooltip("How many flags to choose from.")
+            .draw(ui, &mut self.flag_count, 6, 2..=12, 1.0);
+
+        ui.checkbox(&mut self.similar_colors, "Similar colors")
+            .on_hover_text("The other flags share colors with the correct one.");
+
+        self.region_filter.render(ui);
+    }
+
+    fn create_quiz(&self) -> Quiz {
+        Quiz::CountryFlag(CountryFlagQuiz::with_settings(self.clone()))
+    }
+}
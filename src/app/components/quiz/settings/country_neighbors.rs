@@ -0,0 +1,56 @@
+use crate::app::components::quiz::answer_matching::AnswerMatching;
+use crate::app::components::quiz::region_filter::RegionFilter;
+use crate::app::components::quiz::settings::QuizSettingsTrait;
+use crate::app::components::quiz::types::country_neighbors::CountryNeighborsQuiz;
+use crate::app::components::quiz::Quiz;
+use crate::app::components::settings_slider::SettingsSlider;
+use egui::Ui;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CountryNeighborsQuizSettings {
+    pub max_mistakes: u8,
+    pub show_neighbor_count: bool,
+    #[serde(default)]
+    pub answer_matching: AnswerMatching,
+    #[serde(default)]
+    pub allow_autocomplete: bool,
+    #[serde(default)]
+    pub region_filter: RegionFilter,
+}
+
+impl Default for CountryNeighborsQuizSettings {
+    fn default() -> Self {
+        Self {
+            max_mistakes: 3,
+            show_neighbor_count: true,
+            answer_matching: AnswerMatching::default(),
+            allow_autocomplete: false,
+            region_filter: RegionFilter::default(),
+        }
+    }
+}
+
+impl QuizSettingsTrait for CountryNeighborsQuizSettings {
+    fn render(&mut self, ui: &mut Ui) {
+        SettingsSlider::build()
+            .text("Mistakes")
+            .tooltip("How many wrong countries you can name before the round ends.")
+            .draw(ui, &mut self.max_mistakes, 3, 1..=10, 1.0);
+
+        ui.checkbox(&mut self.show_neighbor_count, "Show neighbor count")
+            .on_hover_text("Show how many neighbors the country has.");
+
+        self.answer_matching.render(ui);
+        ui.checkbox(&mut self.allow_autocomplete, "Autocomplete")
+            .on_hover_text(
+                "Suggest country names while typing, picked suggestions count as assisted answers.",
+            );
+
+        self.region_filter.render(ui);
+    }
+
+    fn create_quiz(&self) -> Quiz {
+        Quiz::CountryNeighbors(CountryNeighborsQuiz::with_settings(self.clone()))
+    }
+}
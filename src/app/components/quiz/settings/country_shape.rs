@@ -0,0 +1,66 @@
+use crate::app::components::quiz::answer_matching::AnswerMatching;
+use crate::app::components::quiz::region_filter::RegionFilter;
+use crate::app::components::quiz::settings::QuizSettingsTrait;
+use crate::app::components::quiz::types::country_shape::CountryShapeQuiz;
+use crate::app::components::quiz::Quiz;
+use crate::app::components::settings_slider::SettingsSlider;
+use egui::Ui;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CountryShapeQuizSettings {
+    pub max_tries: u8,
+    pub random_rotation: bool,
+    pub hide_small_islands: bool,
+    pub outline_only: bool,
+    #[serde(default)]
+    pub answer_matching: AnswerMatching,
+    #[serde(default)]
+    pub allow_autocomplete: bool,
+    #[serde(default)]
+    pub region_filter: RegionFilter,
+}
+
+impl Default for CountryShapeQuizSettings {
+    fn default() -> Self {
+        Self {
+            max_tries: 1,
+            random_rotation: false,
+            hide_small_islands: false,
+            outline_only: false,
+            answer_matching: AnswerMatching::default(),
+            allow_autocomplete: false,
+            region_filter: RegionFilter::default(),
+        }
+    }
+}
+
+impl QuizSettingsTrait for CountryShapeQuizSettings {
+    fn render(&mut self, ui: &mut Ui) {
+        SettingsSlider::build()
+            .text("Tries")
+            .tooltip("How often you can try guessing the shape's country.")
+            .draw(ui, &mut self.max_tries, 1, 1..=10, 1.0);
+
+        ui.checkbox(&mut self.random_rotation, "Random rotation")
+            .on_hover_text("Rotate the shape by a random angle.");
+        ui.checkbox(&mut self.hide_small_islands, "Hide small islands")
+            .on_hover_text(
+                "Only show the parts of the country that aren't tiny compared to its largest part.",
+            );
+        ui.checkbox(&mut self.outline_only, "Outline only")
+            .on_hover_text("Only draw the borders of the country.");
+
+        self.answer_matching.render(ui);
+        ui.checkbox(&mut self.allow_autocomplete, "Autocomplete")
+            .on_hover_text(
+                "Suggest country names while typing, picked suggestions count as assisted answers.",
+            );
+
+        self.region_filter.render(ui);
+    }
+
+    fn create_quiz(&self) -> Quiz {
+        Quiz::CountryShape(CountryShapeQuiz::with_settings(self.clone()))
+    }
+}
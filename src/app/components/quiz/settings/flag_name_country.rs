@@ -1,18 +1,38 @@
-use crate::app::components::quiz::settings::QuizSettingsTrait;
+use crate::app::components::quiz::answer_matching::AnswerMatching;
+use crate::app::components::quiz::distractors::DistractorBias;
+use crate::app::components::quiz::region_filter::RegionFilter;
+use crate::app::components::quiz::settings::{AnswerMode, QuizSettingsTrait};
 use crate::app::components::quiz::types::flag_name_country::FlagNameCountryQuiz;
 use crate::app::components::quiz::Quiz;
 use crate::app::components::settings_slider::SettingsSlider;
 use egui::Ui;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FlagNameCountryQuizSettings {
     pub max_tries: u8,
+    #[serde(default)]
+    pub answer_mode: AnswerMode,
+    #[serde(default)]
+    pub distractor_bias: DistractorBias,
+    #[serde(default)]
+    pub answer_matching: AnswerMatching,
+    #[serde(default)]
+    pub allow_autocomplete: bool,
+    #[serde(default)]
+    pub region_filter: RegionFilter,
 }
 
 impl Default for FlagNameCountryQuizSettings {
     fn default() -> Self {
-        Self { max_tries: 1 }
+        Self {
+            max_tries: 1,
+            answer_mode: AnswerMode::default(),
+            distractor_bias: DistractorBias::default(),
+            answer_matching: AnswerMatching::default(),
+            allow_autocomplete: false,
+            region_filter: RegionFilter::default(),
+        }
     }
 }
 
@@ -22,6 +42,36 @@ impl QuizSettingsTrait for FlagNameCountryQuizSettings {
             .text("Tries")
             .tooltip("How often you can try guessing the flag's country.")
             .draw(ui, &mut self.max_tries, 1, 1..=10, 1.0);
+
+        egui::ComboBox::from_label("Answer mode")
+            .selected_text(self.answer_mode.label())
+            .show_ui(ui, |ui| {
+                for mode in AnswerMode::ALL {
+                    ui.selectable_value(&mut self.answer_mode, mode, mode.label());
+                }
+            });
+
+        ui.add_enabled_ui(self.answer_mode.choice_count().is_some(), |ui| {
+            egui::ComboBox::from_label("Wrong choices")
+                .selected_text(self.distractor_bias.label())
+                .show_ui(ui, |ui| {
+                    for bias in DistractorBias::ALL {
+                        ui.selectable_value(&mut self.distractor_bias, bias, bias.label());
+                    }
+                })
+                .response
+                .on_hover_text("Which countries are offered as wrong answers.");
+        });
+
+        ui.add_enabled_ui(self.answer_mode.choice_count().is_none(), |ui| {
+            self.answer_matching.render(ui);
+            ui.checkbox(&mut self.allow_autocomplete, "Autocomplete")
+                .on_hover_text(
+                "Suggest country names while typing, picked suggestions count as assisted answers.",
+            );
+        });
+
+        self.region_filter.render(ui);
     }
 
     fn create_quiz(&self) -> Quiz {
@@ -0,0 +1,38 @@
+use crate::app::components::quiz::region_filter::RegionFilter;
+use crate::app::components::quiz::settings::QuizSettingsTrait;
+use crate::app::components::quiz::types::locate_country::LocateCountryQuiz;
+use crate::app::components::quiz::Quiz;
+use crate::app::components::settings_slider::SettingsSlider;
+use egui::Ui;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocateCountryQuizSettings {
+    pub max_tries: u8,
+    #[serde(default)]
+    pub region_filter: RegionFilter,
+}
+
+impl Default for LocateCountryQuizSettings {
+    fn default() -> Self {
+        Self {
+            max_tries: 3,
+            region_filter: RegionFilter::default(),
+        }
+    }
+}
+
+impl QuizSettingsTrait for LocateCountryQuizSettings {
+    fn render(&mut self, ui: &mut Ui) {
+        SettingsSlider::build()
+            .text("Tries")
+            .tooltip("How often you can click on the map before the country is revealed.")
+            .draw(ui, &mut self.max_tries, 3, 1..=10, 1.0);
+
+        self.region_filter.render(ui);
+    }
+
+    fn create_quiz(&self) -> Quiz {
+        Quiz::LocateCountry(LocateCountryQuiz::with_settings(self.clone()))
+    }
+}
@@ -0,0 +1,52 @@
+use crate::app::components::quiz::answer_matching::AnswerMatching;
+use crate::app::components::quiz::region_filter::RegionFilter;
+use crate::app::components::quiz::settings::QuizSettingsTrait;
+use crate::app::components::quiz::types::world_countries::WorldCountriesQuiz;
+use crate::app::components::quiz::Quiz;
+use crate::app::components::settings_slider::SettingsSlider;
+use egui::Ui;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldCountriesQuizSettings {
+    pub duration_minutes: u8,
+    #[serde(default)]
+    pub answer_matching: AnswerMatching,
+    #[serde(default)]
+    pub allow_autocomplete: bool,
+    #[serde(default)]
+    pub region_filter: RegionFilter,
+}
+
+impl Default for WorldCountriesQuizSettings {
+    fn default() -> Self {
+        Self {
+            duration_minutes: 15,
+            answer_matching: AnswerMatching::default(),
+            allow_autocomplete: false,
+            region_filter: RegionFilter::default(),
+        }
+    }
+}
+
+impl QuizSettingsTrait for WorldCountriesQuizSettings {
+    fn render(&mut self, ui: &mut Ui) {
+        SettingsSlider::build()
+            .text("Duration")
+            .tooltip("How much time you have to name every country in the world.")
+            .suffix(" min")
+            .draw(ui, &mut self.duration_minutes, 15, 5..=30, 1.0);
+
+        self.answer_matching.render(ui);
+        ui.checkbox(&mut self.allow_autocomplete, "Autocomplete")
+            .on_hover_text(
+                "Suggest country names while typing, picked suggestions count as assisted answers.",
+            );
+
+        self.region_filter.render(ui);
+    }
+
+    fn create_quiz(&self) -> Quiz {
+        Quiz::WorldCountries(WorldCountriesQuiz::with_settings(self.clone()))
+    }
+}
@@ -1,8 +1,25 @@
+use crate::app::components::quiz::stats::capital_location::CapitalLocationQuizStats;
+use crate::app::components::quiz::stats::country_capital::CountryCapitalQuizStats;
+use crate::app::components::quiz::stats::country_flag::CountryFlagQuizStats;
+use crate::app::components::quiz::stats::country_neighbors::CountryNeighborsQuizStats;
+use crate::app::components::quiz::stats::country_shape::CountryShapeQuizStats;
 use crate::app::components::quiz::stats::flag_name_country::FlagNameCountryQuizStats;
+use crate::app::components::quiz::stats::locate_country::LocateCountryQuizStats;
+use crate::app::components::quiz::stats::world_countries::WorldCountriesQuizStats;
+use crate::app::components::quiz::QuizType;
+use crate::get_data;
 use serde::{Deserialize, Serialize};
 
+pub mod capital_location;
+pub mod country_capital;
+pub mod country_flag;
+pub mod country_neighbors;
+pub mod country_shape;
 pub mod flag_name_country;
+pub mod locate_country;
+pub mod world_countries;
 
+#[allow(dead_code)]
 pub trait QuizStatsTrait {
     fn get_common(&self) -> &QuizStatsCommon;
 }
@@ -11,17 +28,68 @@ pub trait QuizStatsTrait {
 pub struct QuizStatsCommon {
     pub started_at_ms: u128,
     pub finished_at_ms: u128,
+    /// What the quiz asked about, e.g. a country code or capital name.
+    #[serde(default)]
+    pub subject: Option<String>,
+    #[serde(default)]
+    pub success: Option<bool>,
+    /// How many answers were given, wrong ones included.
+    #[serde(default)]
+    pub attempts: u32,
+    /// Every given answer in order: typed text, picked country codes or clicked coordinates.
+    #[serde(default)]
+    pub answers: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum QuizStats {
     FlagNameCountry(FlagNameCountryQuizStats),
+    LocateCountry(LocateCountryQuizStats),
+    CountryCapital(CountryCapitalQuizStats),
+    CapitalLocation(CapitalLocationQuizStats),
+    CountryFlag(CountryFlagQuizStats),
+    CountryShape(CountryShapeQuizStats),
+    CountryNeighbors(CountryNeighborsQuizStats),
+    WorldCountries(WorldCountriesQuizStats),
+}
+
+impl QuizStats {
+    /// The country the quiz was about, capitals count towards their country.
+    pub fn subject_country(&self) -> Option<String> {
+        let subject = self.get_common().subject.as_ref()?;
+        let data = get_data();
+        let country = match self.quiz_type() {
+            QuizType::CapitalLocation => data.get_capital_country(subject),
+            _ => data.get_country(subject),
+        };
+        country.map(|country| country.iso_a2.clone())
+    }
+
+    pub fn quiz_type(&self) -> QuizType {
+        match self {
+            QuizStats::FlagNameCountry(_) => QuizType::FlagNameCountry,
+            QuizStats::LocateCountry(_) => QuizType::LocateCountry,
+            QuizStats::CountryCapital(_) => QuizType::CountryCapital,
+            QuizStats::CapitalLocation(_) => QuizType::CapitalLocation,
+            QuizStats::CountryFlag(_) => QuizType::CountryFlag,
+            QuizStats::CountryShape(_) => QuizType::CountryShape,
+            QuizStats::CountryNeighbors(_) => QuizType::CountryNeighbors,
+            QuizStats::WorldCountries(_) => QuizType::WorldCountries,
+        }
+    }
 }
 
 impl QuizStatsTrait for QuizStats {
     fn get_common(&self) -> &QuizStatsCommon {
         match self {
             QuizStats::FlagNameCountry(stats) => stats.get_common(),
+            QuizStats::LocateCountry(stats) => stats.get_common(),
+            QuizStats::CountryCapital(stats) => stats.get_common(),
+            QuizStats::CapitalLocation(stats) => stats.get_common(),
+            QuizStats::CountryFlag(stats) => stats.get_common(),
+            QuizStats::CountryShape(stats) => stats.get_common(),
+            QuizStats::CountryNeighbors(stats) => stats.get_common(),
+            QuizStats::WorldCountries(stats) => stats.get_common(),
         }
     }
 }
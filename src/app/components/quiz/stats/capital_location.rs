@@ -0,0 +1,15 @@
+use crate::app::components::quiz::stats::{QuizStatsCommon, QuizStatsTrait};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapitalLocationQuizStats {
+    pub common: QuizStatsCommon,
+    /// Great-circle distance between the guess and the capital, [`None`] if no guess was made.
+    pub distance_km: Option<f64>,
+}
+
+impl QuizStatsTrait for CapitalLocationQuizStats {
+    fn get_common(&self) -> &QuizStatsCommon {
+        &self.common
+    }
+}
@@ -0,0 +1,17 @@
+use crate::app::components::quiz::answer_matching::AnswerMatch;
+use crate::app::components::quiz::stats::{QuizStatsCommon, QuizStatsTrait};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountryCapitalQuizStats {
+    pub common: QuizStatsCommon,
+    /// How the typed answer matched, [`None`] if the quiz wasn't solved by typing.
+    #[serde(default)]
+    pub answer_match: Option<AnswerMatch>,
+}
+
+impl QuizStatsTrait for CountryCapitalQuizStats {
+    fn get_common(&self) -> &QuizStatsCommon {
+        &self.common
+    }
+}
@@ -0,0 +1,13 @@
+use crate::app::components::quiz::stats::{QuizStatsCommon, QuizStatsTrait};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountryFlagQuizStats {
+    pub common: QuizStatsCommon,
+}
+
+impl QuizStatsTrait for CountryFlagQuizStats {
+    fn get_common(&self) -> &QuizStatsCommon {
+        &self.common
+    }
+}
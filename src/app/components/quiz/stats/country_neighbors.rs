@@ -0,0 +1,21 @@
+use crate::app::components::quiz::stats::{QuizStatsCommon, QuizStatsTrait};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountryNeighborsQuizStats {
+    pub common: QuizStatsCommon,
+    pub found_neighbors: Vec<String>,
+    /// Neighbors that were only accepted thanks to the typo tolerance.
+    #[serde(default)]
+    pub fuzzy_matches: Vec<String>,
+    /// Answers that were picked from the autocomplete suggestions.
+    #[serde(default)]
+    pub assisted_answers: Vec<String>,
+    pub missed_neighbors: Vec<String>,
+}
+
+impl QuizStatsTrait for CountryNeighborsQuizStats {
+    fn get_common(&self) -> &QuizStatsCommon {
+        &self.common
+    }
+}
@@ -1,9 +1,16 @@
+use crate::app::components::quiz::answer_matching::AnswerMatch;
 use crate::app::components::quiz::stats::{QuizStatsCommon, QuizStatsTrait};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FlagNameCountryQuizStats {
     pub common: QuizStatsCommon,
+    /// How the typed answer matched, [`None`] if the quiz wasn't solved by typing.
+    #[serde(default)]
+    pub answer_match: Option<AnswerMatch>,
+    /// Whether the right answer was picked from the autocomplete suggestions.
+    #[serde(default)]
+    pub assisted: bool,
 }
 
 impl QuizStatsTrait for FlagNameCountryQuizStats {
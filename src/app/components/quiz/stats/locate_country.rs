@@ -0,0 +1,13 @@
+use crate::app::components::quiz::stats::{QuizStatsCommon, QuizStatsTrait};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocateCountryQuizStats {
+    pub common: QuizStatsCommon,
+}
+
+impl QuizStatsTrait for LocateCountryQuizStats {
+    fn get_common(&self) -> &QuizStatsCommon {
+        &self.common
+    }
+}
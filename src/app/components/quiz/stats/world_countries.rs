@@ -0,0 +1,23 @@
+use crate::app::components::quiz::stats::{QuizStatsCommon, QuizStatsTrait};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldCountriesQuizStats {
+    pub common: QuizStatsCommon,
+    /// Time spent naming countries, pauses and app restarts excluded.
+    pub elapsed_ms: u128,
+    pub found_countries: Vec<String>,
+    /// Countries that were only accepted thanks to the typo tolerance.
+    #[serde(default)]
+    pub fuzzy_matches: Vec<String>,
+    /// Answers that were picked from the autocomplete suggestions.
+    #[serde(default)]
+    pub assisted_answers: Vec<String>,
+    pub missed_countries: Vec<String>,
+}
+
+impl QuizStatsTrait for WorldCountriesQuizStats {
+    fn get_common(&self) -> &QuizStatsCommon {
+        &self.common
+    }
+}
@@ -1 +1,8 @@
+pub mod capital_location;
+pub mod country_capital;
+pub mod country_flag;
+pub mod country_neighbors;
+pub mod country_shape;
 pub mod flag_name_country;
+pub mod locate_country;
+pub mod world_countries;
@@ -0,0 +1,281 @@
+use crate::app::components::quiz::scheduler::ReviewScheduler;
+use crate::app::components::quiz::settings::capital_location::CapitalLocationQuizSettings;
+use crate::app::components::quiz::stats::capital_location::CapitalLocationQuizStats;
+use crate::app::components::quiz::stats::{QuizStats, QuizStatsCommon};
+use crate::app::components::quiz::{QuizTrait, QuizType};
+use crate::app::components::world_map::{pixels_to_map_units, WorldMapState, WorldMapStatePersist};
+use crate::app::persistence::persistent_object::PersistentObject;
+use crate::data::projection::Projection;
+use crate::get_data;
+use crate::utils::distance::great_circle_distance_km;
+use crate::utils::time::timestamp_ms;
+use egui::{Color32, Pos2, Rect, RichText, Stroke, Ui, Vec2};
+use geo::coord;
+use serde::{Deserialize, Serialize};
+
+const GUESS_COLOR: Color32 = Color32::from_rgb(214, 69, 65);
+const CAPITAL_COLOR: Color32 = Color32::from_rgb(88, 180, 98);
+const MARKER_RADIUS: f32 = 5.0;
+const LINE_WIDTH: f32 = 2.0;
+const MAP_BOTTOM_SPACE: f32 = 40.0;
+
+#[derive(Debug)]
+pub struct CapitalLocationQuiz {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    solution: Option<String>,
+    answers: Vec<String>,
+    guess: Option<Pos2>,
+    distance_km: Option<f64>,
+    settings: CapitalLocationQuizSettings,
+    world_map: WorldMapState,
+}
+
+impl Default for CapitalLocationQuiz {
+    fn default() -> Self {
+        Self {
+            success: None,
+            started_at: None,
+            finished_at: None,
+            solution: None,
+            answers: Vec::new(),
+            guess: None,
+            distance_km: None,
+            settings: CapitalLocationQuizSettings::default(),
+            world_map: WorldMapState::for_quiz(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CapitalLocationQuizState {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    solution: Option<String>,
+    #[serde(default)]
+    answers: Vec<String>,
+    guess: Option<(f32, f32)>,
+    distance_km: Option<f64>,
+    settings: CapitalLocationQuizSettings,
+    world_map: WorldMapStatePersist,
+}
+
+impl PersistentObject for CapitalLocationQuiz {
+    type PersistentState = CapitalLocationQuizState;
+
+    fn save_state(&self) -> Self::PersistentState {
+        CapitalLocationQuizState {
+            success: self.success,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            solution: self.solution.clone(),
+            answers: self.answers.clone(),
+            guess: self.guess.map(|guess| (guess.x, guess.y)),
+            distance_km: self.distance_km,
+            settings: self.settings.clone(),
+            world_map: self.world_map.save_state(),
+        }
+    }
+
+    fn load_state(state: Self::PersistentState) -> Self {
+        Self {
+            success: state.success,
+            started_at: state.started_at,
+            finished_at: state.finished_at,
+            solution: state.solution,
+            answers: state.answers,
+            guess: state.guess.map(|(x, y)| Pos2::new(x, y)),
+            distance_km: state.distance_km,
+            settings: state.settings,
+            world_map: WorldMapState::load_for_quiz(state.world_map),
+        }
+    }
+}
+
+impl CapitalLocationQuiz {
+    pub fn with_settings(settings: CapitalLocationQuizSettings) -> Self {
+        Self {
+            settings,
+            ..Self::default()
+        }
+    }
+
+    fn submit(&mut self, capital_position: Pos2, guess: Pos2) {
+        let distance_km = great_circle_distance_km(
+            coord! { x: guess.x, y: -guess.y },
+            coord! { x: capital_position.x, y: -capital_position.y },
+        );
+
+        self.answers
+            .push(format!("{:.2}, {:.2}", -guess.y, guess.x));
+        self.guess = Some(guess);
+        self.distance_km = Some(distance_km);
+        self.finish(distance_km <= self.settings.close_distance_km as f64);
+        self.world_map
+            .focus_rect(Rect::from_two_pos(guess, capital_position));
+    }
+
+    fn result_text(&self) -> String {
+        let Some(distance_km) = self.distance_km else {
+            return "No guess".to_string();
+        };
+
+        let verdict = if distance_km <= self.settings.perfect_distance_km as f64 {
+            "Perfect!"
+        } else if distance_km <= self.settings.close_distance_km as f64 {
+            "Close!"
+        } else {
+            "Miss"
+        };
+
+        format!("{verdict} You were {distance_km:.0} km off.")
+    }
+}
+
+impl QuizTrait for CapitalLocationQuiz {
+    fn render(&mut self, ui: &mut Ui) -> Option<bool> {
+        let capital_name = self.solution.clone()?;
+        let capital = get_data().get_capital(&capital_name).cloned()?;
+        let capital_position = Pos2::new(capital.coordinates.x, -capital.coordinates.y);
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(10.0);
+            ui.label(RichText::new(format!("Where is {}?", capital.name)).size(30.0));
+            if self.settings.show_country
+                && let Some(country) = get_data().get_capital_country(&capital_name)
+            {
+                ui.label(format!("Capital of {}", country.common_name));
+            }
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| match self.success {
+                None => {
+                    if ui.button("Dunno").clicked() {
+                        self.finish(false);
+                        self.world_map
+                            .focus_rect(Rect::from_center_size(capital_position, Vec2::ZERO));
+                    }
+                }
+                Some(_) => {
+                    ui.label(self.result_text());
+                }
+            });
+            ui.add_space(5.0);
+
+            let map_size = Vec2::new(
+                ui.available_width(),
+                (ui.available_height() - MAP_BOTTOM_SPACE).max(0.0),
+            );
+            let guess = self.guess;
+            let finished = self.success.is_some();
+            let projection = self.world_map.projection();
+            let map_response = ui
+                .allocate_ui(map_size, |ui| {
+                    self.world_map.draw_with_overlay(ui, |ui| {
+                        if finished {
+                            draw_result(ui, projection, guess, capital_position);
+                        }
+                    })
+                })
+                .inner;
+
+            if !finished && let Some(guess) = map_response.clicked_position {
+                self.submit(capital_position, guess);
+            }
+        });
+
+        self.success
+    }
+
+    fn start(&mut self, scheduler: &ReviewScheduler) {
+        self.reset();
+        self.started_at = Some(timestamp_ms());
+
+        let capital_names: Vec<String> = get_data().get_capitals().keys().cloned().collect();
+        let region_capitals: Vec<String> = capital_names
+            .iter()
+            .filter(|name| {
+                get_data()
+                    .get_capital_country(name)
+                    .is_some_and(|country| self.settings.region_filter.contains(&country.iso_a2))
+            })
+            .cloned()
+            .collect();
+        let candidates = if region_capitals.is_empty() {
+            &capital_names
+        } else {
+            &region_capitals
+        };
+        // Without capital data there's nothing to ask, the quiz stays empty instead of panicking.
+        self.solution = scheduler.pick(QuizType::CapitalLocation, candidates);
+    }
+
+    fn finish(&mut self, success: bool) {
+        self.success = Some(success);
+        self.finished_at = Some(timestamp_ms());
+    }
+
+    fn has_started(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    fn is_successful(&self) -> Option<bool> {
+        self.success
+    }
+
+    fn reset(&mut self) {
+        self.success = None;
+        self.started_at = None;
+        self.solution = None;
+        self.answers.clear();
+        self.guess = None;
+        self.distance_km = None;
+        self.world_map.reset_view();
+    }
+
+    fn collect_stats(&self) -> Option<QuizStats> {
+        let common = QuizStatsCommon {
+            started_at_ms: self.started_at?,
+            finished_at_ms: self.finished_at?,
+            subject: self.solution.clone(),
+            success: self.success,
+            attempts: self.answers.len() as u32,
+            answers: self.answers.clone(),
+        };
+
+        let stats = CapitalLocationQuizStats {
+            common,
+            distance_km: self.distance_km,
+        };
+
+        Some(QuizStats::CapitalLocation(stats))
+    }
+}
+
+/// Positions are in `(longitude, -latitude)`, the overlay is drawn in projected map coordinates.
+fn draw_result(ui: &mut Ui, projection: Projection, guess: Option<Pos2>, capital_position: Pos2) {
+    let radius = pixels_to_map_units(ui, MARKER_RADIUS);
+    let line_width = pixels_to_map_units(ui, LINE_WIDTH);
+    let project = |position: Pos2| projection.project(position.x, -position.y);
+
+    // Markers on the far side of the globe aren't drawn.
+    let guess = guess.and_then(project);
+    let Some(capital_position) = project(capital_position) else {
+        if let Some(guess) = guess {
+            ui.painter().circle_filled(guess, radius, GUESS_COLOR);
+        }
+        return;
+    };
+
+    if let Some(guess) = guess {
+        ui.painter().line_segment(
+            [guess, capital_position],
+            Stroke::new(line_width, Color32::BLACK),
+        );
+        ui.painter().circle_filled(guess, radius, GUESS_COLOR);
+    }
+    ui.painter()
+        .circle_filled(capital_position, radius, CAPITAL_COLOR);
+}
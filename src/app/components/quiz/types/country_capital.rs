@@ -0,0 +1,264 @@
+use crate::app::components::quiz::answer_matching::{match_capital_among, AnswerMatch};
+use crate::app::components::quiz::scheduler::ReviewScheduler;
+use crate::app::components::quiz::settings::country_capital::CountryCapitalQuizSettings;
+use crate::app::components::quiz::stats::country_capital::CountryCapitalQuizStats;
+use crate::app::components::quiz::stats::{QuizStats, QuizStatsCommon};
+use crate::app::components::quiz::{QuizTrait, QuizType};
+use crate::app::persistence::persistent_object::PersistentObject;
+use crate::get_data;
+use crate::utils::time::timestamp_ms;
+use eframe::emath::Vec2;
+use egui::{Align, Key, Layout, RichText, Ui};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default)]
+pub struct CountryCapitalQuiz {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    solution: Option<String>,
+    answers: Vec<String>,
+    found_capitals: Vec<String>,
+    answer: String,
+    tries: u8,
+    answer_match: Option<AnswerMatch>,
+    settings: CountryCapitalQuizSettings,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CountryCapitalQuizState {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    solution: Option<String>,
+    #[serde(default)]
+    answers: Vec<String>,
+    found_capitals: Vec<String>,
+    answer: String,
+    tries: u8,
+    #[serde(default)]
+    answer_match: Option<AnswerMatch>,
+    settings: CountryCapitalQuizSettings,
+}
+
+impl PersistentObject for CountryCapitalQuiz {
+    type PersistentState = CountryCapitalQuizState;
+
+    fn save_state(&self) -> Self::PersistentState {
+        CountryCapitalQuizState {
+            success: self.success,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            solution: self.solution.clone(),
+            answers: self.answers.clone(),
+            found_capitals: self.found_capitals.clone(),
+            answer: self.answer.clone(),
+            tries: self.tries,
+            answer_match: self.answer_match,
+            settings: self.settings.clone(),
+        }
+    }
+
+    fn load_state(state: Self::PersistentState) -> Self {
+        Self {
+            success: state.success,
+            started_at: state.started_at,
+            finished_at: state.finished_at,
+            solution: state.solution,
+            answers: state.answers,
+            found_capitals: state.found_capitals,
+            answer: state.answer,
+            tries: state.tries,
+            answer_match: state.answer_match,
+            settings: state.settings,
+        }
+    }
+}
+
+impl CountryCapitalQuiz {
+    pub fn with_settings(settings: CountryCapitalQuizSettings) -> Self {
+        Self {
+            settings,
+            ..Self::default()
+        }
+    }
+
+    fn tries_left(&self) -> u8 {
+        self.settings.max_tries - self.tries
+    }
+
+    fn capital_names(country_code: &str) -> Vec<String> {
+        get_data()
+            .get_country_capitals(country_code)
+            .iter()
+            .map(|capital| capital.name.clone())
+            .collect()
+    }
+
+    fn submit(&mut self, country_code: &str) {
+        self.answers.push(self.answer.trim().to_string());
+        let capital_names = Self::capital_names(country_code);
+        let matched_capital =
+            match_capital_among(&self.answer, &capital_names, &self.settings.answer_matching);
+        self.answer.clear();
+
+        if let Some((capital, answer_match)) = matched_capital {
+            self.answer_match = Some(AnswerMatch::combine(self.answer_match, answer_match));
+            if !self.found_capitals.contains(&capital) {
+                self.found_capitals.push(capital);
+            }
+
+            if !self.settings.all_capitals_required
+                || self.found_capitals.len() >= capital_names.len()
+            {
+                self.finish(true);
+            }
+            return;
+        }
+
+        self.tries += 1;
+        if self.tries >= self.settings.max_tries {
+            self.finish(false);
+        }
+    }
+}
+
+impl QuizTrait for CountryCapitalQuiz {
+    fn render(&mut self, ui: &mut Ui) -> Option<bool> {
+        let country_code = self.solution.clone()?;
+        let country = get_data().get_country(&country_code).cloned()?;
+        let capital_names = Self::capital_names(&country_code);
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(40.0);
+
+            ui.label(
+                RichText::new(format!("What is the capital of {}?", country.common_name))
+                    .size(30.0),
+            );
+            ui.add_space(10.0);
+
+            if self.settings.show_flag
+                && let Some(flag_image) =
+                    get_data().get_country_flag_image(&country_code, Vec2::new(300.0, 300.0))
+            {
+                ui.add(flag_image);
+            }
+
+            if self.settings.all_capitals_required && capital_names.len() > 1 {
+                ui.add_space(10.0);
+                ui.label(format!(
+                    "This country has {} capitals, found: {}",
+                    capital_names.len(),
+                    self.found_capitals.len()
+                ));
+                for capital in &self.found_capitals {
+                    ui.label(RichText::new(capital).strong());
+                }
+            }
+
+            match self.success {
+                None => {
+                    ui.add_space(20.0);
+
+                    let text_edit_response = ui.text_edit_singleline(&mut self.answer);
+                    if text_edit_response.lost_focus()
+                        && ui.input_mut(|input| input.key_pressed(Key::Enter))
+                    {
+                        self.submit(&country_code);
+                    }
+                    text_edit_response.request_focus();
+
+                    ui.add_space(5.0);
+
+                    ui.columns(2, |columns| {
+                        columns[0].allocate_ui_with_layout(
+                            Vec2::ZERO,
+                            Layout::right_to_left(Align::Center),
+                            |ui| {
+                                if ui.button("Submit").clicked() {
+                                    self.submit(&country_code);
+                                }
+                                if self.settings.max_tries > 1 {
+                                    ui.label(format!("Tries left: {}", self.tries_left()));
+                                }
+                            },
+                        );
+                        columns[1].allocate_ui_with_layout(
+                            Vec2::ZERO,
+                            Layout::left_to_right(Align::Center),
+                            |ui| {
+                                if ui.button("Dunno").clicked() {
+                                    self.finish(false);
+                                }
+                            },
+                        );
+                    });
+                }
+                Some(true) => {
+                    ui.add_space(20.0);
+                    ui.label("Correct!");
+                }
+                Some(false) => {
+                    ui.add_space(20.0);
+                    ui.label(format!("Solution: {}", capital_names.join(", ")));
+                }
+            }
+        });
+
+        self.success
+    }
+
+    fn start(&mut self, scheduler: &ReviewScheduler) {
+        self.reset();
+        self.started_at = Some(timestamp_ms());
+
+        let candidates = self
+            .settings
+            .region_filter
+            .candidates(|code| !get_data().get_country_capitals(code).is_empty());
+        self.solution = scheduler.pick(QuizType::CountryCapital, &candidates);
+    }
+
+    fn finish(&mut self, success: bool) {
+        self.success = Some(success);
+        self.finished_at = Some(timestamp_ms());
+    }
+
+    fn has_started(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    fn is_successful(&self) -> Option<bool> {
+        self.success
+    }
+
+    fn reset(&mut self) {
+        self.success = None;
+        self.started_at = None;
+        self.solution = None;
+        self.answers.clear();
+        self.found_capitals.clear();
+        self.answer = String::new();
+        self.tries = 0;
+        self.answer_match = None;
+    }
+
+    fn collect_stats(&self) -> Option<QuizStats> {
+        let common = QuizStatsCommon {
+            started_at_ms: self.started_at?,
+            finished_at_ms: self.finished_at?,
+            subject: self.solution.clone(),
+            success: self.success,
+            attempts: self.answers.len() as u32,
+            answers: self.answers.clone(),
+        };
+
+        let stats = CountryCapitalQuizStats {
+            common,
+            answer_match: self.answer_match,
+        };
+
+        Some(QuizStats::CountryCapital(stats))
+    }
+}
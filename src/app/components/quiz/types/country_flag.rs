@@ -0,0 +1,269 @@
+use crate::app::components::quiz::distractors::{pick_distractors, DistractorBias};
+use crate::app::components::quiz::scheduler::ReviewScheduler;
+use crate::app::components::quiz::settings::country_flag::CountryFlagQuizSettings;
+use crate::app::components::quiz::stats::country_flag::CountryFlagQuizStats;
+use crate::app::components::quiz::stats::{QuizStats, QuizStatsCommon};
+use crate::app::components::quiz::{QuizTrait, QuizType};
+use crate::app::persistence::persistent_object::PersistentObject;
+use crate::get_data;
+use crate::utils::time::timestamp_ms;
+use egui::{Color32, CursorIcon, RichText, Sense, Stroke, StrokeKind, Ui, Vec2};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+const FLAG_SIZE: Vec2 = Vec2::new(180.0, 120.0);
+const FLAG_SPACING: f32 = 12.0;
+const MAX_COLUMNS: usize = 4;
+const HIGHLIGHT_WIDTH: f32 = 4.0;
+const WRONG_CHOICE_COLOR: Color32 = Color32::from_rgb(214, 69, 65);
+const SOLUTION_COLOR: Color32 = Color32::from_rgb(88, 180, 98);
+const WRONG_CHOICE_TINT: Color32 = Color32::from_gray(90);
+
+#[derive(Debug, Default)]
+pub struct CountryFlagQuiz {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    solution: Option<String>,
+    answers: Vec<String>,
+    choices: Vec<String>,
+    wrong_choices: Vec<String>,
+    tries: u8,
+    settings: CountryFlagQuizSettings,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CountryFlagQuizState {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    solution: Option<String>,
+    #[serde(default)]
+    answers: Vec<String>,
+    choices: Vec<String>,
+    wrong_choices: Vec<String>,
+    tries: u8,
+    settings: CountryFlagQuizSettings,
+}
+
+impl PersistentObject for CountryFlagQuiz {
+    type PersistentState = CountryFlagQuizState;
+
+    fn save_state(&self) -> Self::PersistentState {
+        CountryFlagQuizState {
+            success: self.success,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            solution: self.solution.clone(),
+            answers: self.answers.clone(),
+            choices: self.choices.clone(),
+            wrong_choices: self.wrong_choices.clone(),
+            tries: self.tries,
+            settings: self.settings.clone(),
+        }
+    }
+
+    fn load_state(state: Self::PersistentState) -> Self {
+        Self {
+            success: state.success,
+            started_at: state.started_at,
+            finished_at: state.finished_at,
+            solution: state.solution,
+            answers: state.answers,
+            choices: state.choices,
+            wrong_choices: state.wrong_choices,
+            tries: state.tries,
+            settings: state.settings,
+        }
+    }
+}
+
+impl CountryFlagQuiz {
+    pub fn with_settings(settings: CountryFlagQuizSettings) -> Self {
+        Self {
+            settings,
+            ..Self::default()
+        }
+    }
+
+    fn tries_left(&self) -> u8 {
+        self.settings.max_tries - self.tries
+    }
+
+    fn submit(&mut self, country_code: &str, choice: String) {
+        self.answers.push(choice.clone());
+        self.tries += 1;
+        if choice == country_code {
+            self.finish(true);
+            return;
+        }
+
+        self.wrong_choices.push(choice);
+        if self.tries >= self.settings.max_tries {
+            self.finish(false);
+        }
+    }
+
+    fn render_flags(&mut self, ui: &mut Ui, country_code: &str) -> Option<String> {
+        let data = get_data();
+        let mut picked_choice = None;
+        let columns = self.choices.len().div_ceil(2).clamp(1, MAX_COLUMNS);
+
+        egui::Grid::new("country_flag_choices")
+            .num_columns(columns)
+            .spacing(Vec2::splat(FLAG_SPACING))
+            .show(ui, |ui| {
+                for (index, choice) in self.choices.iter().enumerate() {
+                    let Some(flag_image) = data.get_country_flag_image(choice, FLAG_SIZE) else {
+                        continue;
+                    };
+
+                    let is_wrong_choice = self.wrong_choices.contains(choice);
+                    let flag_image = if is_wrong_choice {
+                        flag_image.tint(WRONG_CHOICE_TINT)
+                    } else {
+                        flag_image
+                    };
+
+                    let response = ui.add(flag_image.sense(Sense::click()));
+                    let highlight = if is_wrong_choice {
+                        Some(WRONG_CHOICE_COLOR)
+                    } else if self.success.is_some() && choice == country_code {
+                        Some(SOLUTION_COLOR)
+                    } else {
+                        None
+                    };
+                    if let Some(color) = highlight {
+                        ui.painter().rect_stroke(
+                            response.rect,
+                            0.0,
+                            Stroke::new(HIGHLIGHT_WIDTH, color),
+                            StrokeKind::Outside,
+                        );
+                    }
+
+                    if self.success.is_none() && !is_wrong_choice {
+                        if response.hovered() {
+                            ui.output_mut(|o| o.cursor_icon = CursorIcon::PointingHand);
+                        }
+                        if response.clicked() {
+                            picked_choice = Some(choice.clone());
+                        }
+                    }
+
+                    if (index + 1) % columns == 0 {
+                        ui.end_row();
+                    }
+                }
+            });
+
+        picked_choice
+    }
+}
+
+impl QuizTrait for CountryFlagQuiz {
+    fn render(&mut self, ui: &mut Ui) -> Option<bool> {
+        let country_code = self.solution.clone()?;
+        let country = get_data().get_country(&country_code).cloned()?;
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(40.0);
+
+            ui.label(
+                RichText::new(format!("Which is the flag of {}?", country.common_name)).size(30.0),
+            );
+            ui.add_space(20.0);
+
+            if let Some(choice) = self.render_flags(ui, &country_code) {
+                self.submit(&country_code, choice);
+            }
+
+            ui.add_space(20.0);
+            match self.success {
+                None => {
+                    ui.horizontal(|ui| {
+                        if ui.button("Dunno").clicked() {
+                            self.finish(false);
+                        }
+                        if self.settings.max_tries > 1 {
+                            ui.label(format!("Tries left: {}", self.tries_left()));
+                        }
+                    });
+                }
+                Some(true) => {
+                    ui.label("Correct!");
+                }
+                Some(false) => {
+                    ui.label("The correct flag is highlighted.");
+                }
+            }
+        });
+
+        self.success
+    }
+
+    fn start(&mut self, scheduler: &ReviewScheduler) {
+        self.reset();
+        self.started_at = Some(timestamp_ms());
+
+        let mut rng = rand::rng();
+        let country_codes = self.settings.region_filter.country_codes();
+        self.solution = scheduler.pick(QuizType::CountryFlag, &country_codes);
+        let Some(country_code) = &self.solution else {
+            return;
+        };
+
+        let bias = if self.settings.similar_colors {
+            DistractorBias::SimilarFlags
+        } else {
+            DistractorBias::Random
+        };
+        let mut choices = pick_distractors(
+            country_code,
+            &country_codes,
+            self.settings.flag_count.saturating_sub(1) as usize,
+            bias,
+        );
+        choices.push(country_code.clone());
+        choices.shuffle(&mut rng);
+        self.choices = choices;
+    }
+
+    fn finish(&mut self, success: bool) {
+        self.success = Some(success);
+        self.finished_at = Some(timestamp_ms());
+    }
+
+    fn has_started(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    fn is_successful(&self) -> Option<bool> {
+        self.success
+    }
+
+    fn reset(&mut self) {
+        self.success = None;
+        self.started_at = None;
+        self.solution = None;
+        self.answers.clear();
+        self.choices.clear();
+        self.wrong_choices.clear();
+        self.tries = 0;
+    }
+
+    fn collect_stats(&self) -> Option<QuizStats> {
+        let common = QuizStatsCommon {
+            started_at_ms: self.started_at?,
+            finished_at_ms: self.finished_at?,
+            subject: self.solution.clone(),
+            success: self.success,
+            attempts: self.answers.len() as u32,
+            answers: self.answers.clone(),
+        };
+
+        let stats = CountryFlagQuizStats { common };
+
+        Some(QuizStats::CountryFlag(stats))
+    }
+}
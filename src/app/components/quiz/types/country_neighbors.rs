@@ -0,0 +1,328 @@
+use crate::app::components::country_autocomplete::CountryAutocomplete;
+use crate::app::components::quiz::answer_matching::{match_country_among, AnswerMatch};
+use crate::app::components::quiz::scheduler::ReviewScheduler;
+use crate::app::components::quiz::settings::country_neighbors::CountryNeighborsQuizSettings;
+use crate::app::components::quiz::stats::country_neighbors::CountryNeighborsQuizStats;
+use crate::app::components::quiz::stats::{QuizStats, QuizStatsCommon};
+use crate::app::components::quiz::{QuizTrait, QuizType};
+use crate::app::components::world_map::{WorldMapState, WorldMapStatePersist};
+use crate::app::persistence::persistent_object::PersistentObject;
+use crate::get_data;
+use crate::utils::time::timestamp_ms;
+use egui::{Color32, Key, RichText, Ui, Vec2};
+use serde::{Deserialize, Serialize};
+
+const COUNTRY_COLOR: Color32 = Color32::from_rgb(236, 214, 160);
+const FOUND_COLOR: Color32 = Color32::from_rgb(88, 180, 98);
+const MISSED_COLOR: Color32 = Color32::from_rgb(214, 69, 65);
+const MAP_BOTTOM_SPACE: f32 = 40.0;
+
+#[derive(Debug)]
+pub struct CountryNeighborsQuiz {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    solution: Option<String>,
+    answers: Vec<String>,
+    found_neighbors: Vec<String>,
+    fuzzy_matches: Vec<String>,
+    assisted_answers: Vec<String>,
+    answer: String,
+    answer_assisted: bool,
+    mistakes: u8,
+    settings: CountryNeighborsQuizSettings,
+    world_map: WorldMapState,
+}
+
+impl Default for CountryNeighborsQuiz {
+    fn default() -> Self {
+        Self {
+            success: None,
+            started_at: None,
+            finished_at: None,
+            solution: None,
+            answers: Vec::new(),
+            found_neighbors: Vec::new(),
+            fuzzy_matches: Vec::new(),
+            assisted_answers: Vec::new(),
+            answer: String::new(),
+            answer_assisted: false,
+            mistakes: 0,
+            settings: CountryNeighborsQuizSettings::default(),
+            world_map: WorldMapState::for_quiz(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CountryNeighborsQuizState {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    solution: Option<String>,
+    #[serde(default)]
+    answers: Vec<String>,
+    found_neighbors: Vec<String>,
+    #[serde(default)]
+    fuzzy_matches: Vec<String>,
+    #[serde(default)]
+    assisted_answers: Vec<String>,
+    answer: String,
+    mistakes: u8,
+    settings: CountryNeighborsQuizSettings,
+    world_map: WorldMapStatePersist,
+}
+
+impl PersistentObject for CountryNeighborsQuiz {
+    type PersistentState = CountryNeighborsQuizState;
+
+    fn save_state(&self) -> Self::PersistentState {
+        CountryNeighborsQuizState {
+            success: self.success,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            solution: self.solution.clone(),
+            answers: self.answers.clone(),
+            found_neighbors: self.found_neighbors.clone(),
+            fuzzy_matches: self.fuzzy_matches.clone(),
+            assisted_answers: self.assisted_answers.clone(),
+            answer: self.answer.clone(),
+            mistakes: self.mistakes,
+            settings: self.settings.clone(),
+            world_map: self.world_map.save_state(),
+        }
+    }
+
+    fn load_state(state: Self::PersistentState) -> Self {
+        Self {
+            success: state.success,
+            started_at: state.started_at,
+            finished_at: state.finished_at,
+            solution: state.solution,
+            answers: state.answers,
+            found_neighbors: state.found_neighbors,
+            fuzzy_matches: state.fuzzy_matches,
+            assisted_answers: state.assisted_answers,
+            answer: state.answer,
+            answer_assisted: false,
+            mistakes: state.mistakes,
+            settings: state.settings,
+            world_map: WorldMapState::load_for_quiz(state.world_map),
+        }
+    }
+}
+
+impl CountryNeighborsQuiz {
+    pub fn with_settings(settings: CountryNeighborsQuizSettings) -> Self {
+        Self {
+            settings,
+            ..Self::default()
+        }
+    }
+
+    fn mistakes_left(&self) -> u8 {
+        self.settings.max_mistakes - self.mistakes
+    }
+
+    fn missed_neighbors(&self, country_code: &str) -> Vec<String> {
+        get_data()
+            .get_neighbors(country_code)
+            .iter()
+            .filter(|neighbor| !self.found_neighbors.contains(neighbor))
+            .cloned()
+            .collect()
+    }
+
+    fn submit(&mut self, country_code: &str) {
+        let answer = std::mem::take(&mut self.answer);
+        let assisted = std::mem::take(&mut self.answer_assisted);
+        if answer.trim().is_empty() {
+            return;
+        }
+        self.answers.push(answer.trim().to_string());
+
+        let neighbors = get_data().get_neighbors(country_code).to_vec();
+        let Some((neighbor, answer_match)) =
+            match_country_among(&answer, &neighbors, &self.settings.answer_matching)
+        else {
+            self.mistakes += 1;
+            if self.mistakes >= self.settings.max_mistakes {
+                self.finish(false);
+            }
+            return;
+        };
+
+        if !self.found_neighbors.contains(&neighbor) {
+            if answer_match == AnswerMatch::Fuzzy {
+                self.fuzzy_matches.push(neighbor.clone());
+            }
+            if assisted {
+                self.assisted_answers.push(neighbor.clone());
+            }
+            self.found_neighbors.push(neighbor);
+        }
+        if self.found_neighbors.len() >= neighbors.len() {
+            self.finish(true);
+        }
+    }
+
+    fn update_highlights(&mut self, country_code: &str) {
+        self.world_map.clear_highlights();
+        self.world_map
+            .highlight_country(country_code, COUNTRY_COLOR);
+        for neighbor in &self.found_neighbors {
+            self.world_map.highlight_country(neighbor, FOUND_COLOR);
+        }
+        if self.success.is_some() {
+            for neighbor in self.missed_neighbors(country_code) {
+                self.world_map.highlight_country(&neighbor, MISSED_COLOR);
+            }
+        }
+    }
+
+    fn focus_neighborhood(&mut self, country_code: &str) {
+        let data = get_data();
+        let bounds = std::iter::once(country_code)
+            .chain(data.get_neighbors(country_code).iter().map(|c| c.as_str()))
+            .filter_map(|code| data.get_country_bounds(code))
+            .reduce(|a, b| a.union(b));
+        if let Some(bounds) = bounds {
+            self.world_map.focus_rect(bounds);
+        }
+    }
+}
+
+impl QuizTrait for CountryNeighborsQuiz {
+    fn render(&mut self, ui: &mut Ui) -> Option<bool> {
+        let country_code = self.solution.clone()?;
+        let country = get_data().get_country(&country_code).cloned()?;
+        let neighbor_count = get_data().get_neighbors(&country_code).len();
+
+        self.update_highlights(&country_code);
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(10.0);
+            ui.label(
+                RichText::new(format!("Name all neighbors of {}!", country.common_name)).size(30.0),
+            );
+            ui.add_space(5.0);
+
+            let progress = if self.settings.show_neighbor_count {
+                format!("Found {} of {}", self.found_neighbors.len(), neighbor_count)
+            } else {
+                format!("Found {}", self.found_neighbors.len())
+            };
+
+            match self.success {
+                None => {
+                    let autocomplete = CountryAutocomplete::new("country_neighbors_answer")
+                        .enabled(self.settings.allow_autocomplete)
+                        .draw(ui, &mut self.answer);
+                    autocomplete.update_assisted(&mut self.answer_assisted);
+                    let text_edit_response = autocomplete.response;
+                    if text_edit_response.lost_focus()
+                        && ui.input_mut(|input| input.key_pressed(Key::Enter))
+                    {
+                        self.submit(&country_code);
+                    }
+                    text_edit_response.request_focus();
+
+                    ui.add_space(5.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("Submit").clicked() {
+                            self.submit(&country_code);
+                        }
+                        if ui.button("Give up").clicked() {
+                            self.finish(false);
+                        }
+                        ui.label(progress);
+                        ui.label(format!("Mistakes left: {}", self.mistakes_left()));
+                    });
+                }
+                Some(true) => {
+                    ui.label(format!("Correct! {progress}"));
+                }
+                Some(false) => {
+                    let missed_names: Vec<String> = self
+                        .missed_neighbors(&country_code)
+                        .iter()
+                        .filter_map(|code| get_data().get_country(code).cloned())
+                        .map(|country| country.common_name.clone())
+                        .collect();
+                    ui.label(format!("Missed: {}", missed_names.join(", ")));
+                }
+            }
+            ui.add_space(5.0);
+
+            let map_size = Vec2::new(
+                ui.available_width(),
+                (ui.available_height() - MAP_BOTTOM_SPACE).max(0.0),
+            );
+            ui.allocate_ui(map_size, |ui| self.world_map.draw(ui));
+        });
+
+        self.success
+    }
+
+    fn start(&mut self, scheduler: &ReviewScheduler) {
+        self.reset();
+        self.started_at = Some(timestamp_ms());
+
+        let candidates = self
+            .settings
+            .region_filter
+            .candidates(|code| !get_data().get_neighbors(code).is_empty());
+        self.solution = scheduler.pick(QuizType::CountryNeighbors, &candidates);
+        if let Some(country_code) = self.solution.clone() {
+            self.focus_neighborhood(&country_code);
+        }
+    }
+
+    fn finish(&mut self, success: bool) {
+        self.success = Some(success);
+        self.finished_at = Some(timestamp_ms());
+    }
+
+    fn has_started(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    fn is_successful(&self) -> Option<bool> {
+        self.success
+    }
+
+    fn reset(&mut self) {
+        self.success = None;
+        self.started_at = None;
+        self.solution = None;
+        self.answers.clear();
+        self.found_neighbors.clear();
+        self.fuzzy_matches.clear();
+        self.answer_assisted = false;
+        self.assisted_answers.clear();
+        self.answer = String::new();
+        self.mistakes = 0;
+        self.world_map.clear_highlights();
+    }
+
+    fn collect_stats(&self) -> Option<QuizStats> {
+        let common = QuizStatsCommon {
+            started_at_ms: self.started_at?,
+            finished_at_ms: self.finished_at?,
+            subject: self.solution.clone(),
+            success: self.success,
+            attempts: self.answers.len() as u32,
+            answers: self.answers.clone(),
+        };
+
+        let stats = CountryNeighborsQuizStats {
+            common,
+            found_neighbors: self.found_neighbors.clone(),
+            fuzzy_matches: self.fuzzy_matches.clone(),
+            assisted_answers: self.assisted_answers.clone(),
+            missed_neighbors: self.missed_neighbors(self.solution.as_ref()?),
+        };
+
+        Some(QuizStats::CountryNeighbors(stats))
+    }
+}
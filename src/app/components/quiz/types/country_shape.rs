@@ -0,0 +1,484 @@
+use crate::app::components::country_autocomplete::CountryAutocomplete;
+use crate::app::components::map_theme::MapTheme;
+use crate::app::components::quiz::answer_matching::{match_country_name, AnswerMatch};
+use crate::app::components::quiz::scheduler::ReviewScheduler;
+use crate::app::components::quiz::settings::country_shape::CountryShapeQuizSettings;
+use crate::app::components::quiz::stats::country_shape::CountryShapeQuizStats;
+use crate::app::components::quiz::stats::{QuizStats, QuizStatsCommon};
+use crate::app::components::quiz::{QuizTrait, QuizType};
+use crate::app::components::world_map::pixels_to_map_units;
+use crate::app::persistence::persistent_object::PersistentObject;
+use crate::get_data;
+use crate::utils::time::timestamp_ms;
+use egui::{Align, Color32, Key, Layout, Mesh, Pos2, Rect, RichText, Shape, Stroke, Ui, Vec2};
+use geo::Area;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+use std::sync::Arc;
+
+const SHAPE_SIZE: Vec2 = Vec2::new(600.0, 400.0);
+const SHAPE_MARGIN: f32 = 0.05;
+const OUTLINE_WIDTH: f32 = 1.5;
+/// Polygons smaller than this fraction of the country's largest polygon count as small islands.
+const SMALL_ISLAND_RATIO: f64 = 0.02;
+
+#[derive(Debug, Default)]
+pub struct CountryShapeQuiz {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    solution: Option<String>,
+    answers: Vec<String>,
+    rotation: f32,
+    answer: String,
+    answer_assisted: bool,
+    tries: u8,
+    answer_match: Option<AnswerMatch>,
+    assisted: bool,
+    settings: CountryShapeQuizSettings,
+    scene_rect: Option<Rect>,
+    shape: Option<CountryShape>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CountryShapeQuizState {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    solution: Option<String>,
+    #[serde(default)]
+    answers: Vec<String>,
+    rotation: f32,
+    answer: String,
+    tries: u8,
+    #[serde(default)]
+    answer_match: Option<AnswerMatch>,
+    #[serde(default)]
+    assisted: bool,
+    settings: CountryShapeQuizSettings,
+}
+
+impl PersistentObject for CountryShapeQuiz {
+    type PersistentState = CountryShapeQuizState;
+
+    fn save_state(&self) -> Self::PersistentState {
+        CountryShapeQuizState {
+            success: self.success,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            solution: self.solution.clone(),
+            answers: self.answers.clone(),
+            rotation: self.rotation,
+            answer: self.answer.clone(),
+            tries: self.tries,
+            answer_match: self.answer_match,
+            assisted: self.assisted,
+            settings: self.settings.clone(),
+        }
+    }
+
+    fn load_state(state: Self::PersistentState) -> Self {
+        Self {
+            success: state.success,
+            started_at: state.started_at,
+            finished_at: state.finished_at,
+            solution: state.solution,
+            answers: state.answers,
+            rotation: state.rotation,
+            answer: state.answer,
+            answer_assisted: false,
+            tries: state.tries,
+            answer_match: state.answer_match,
+            assisted: state.assisted,
+            settings: state.settings,
+            scene_rect: None,
+            shape: None,
+        }
+    }
+}
+
+impl CountryShapeQuiz {
+    pub fn with_settings(settings: CountryShapeQuizSettings) -> Self {
+        Self {
+            settings,
+            ..Self::default()
+        }
+    }
+
+    fn tries_left(&self) -> u8 {
+        self.settings.max_tries - self.tries
+    }
+
+    fn submit(&mut self, country_code: &str) {
+        self.answers.push(self.answer.trim().to_string());
+        self.answer_match =
+            match_country_name(&self.answer, country_code, &self.settings.answer_matching);
+        self.assisted = self.answer_match.is_some() && std::mem::take(&mut self.answer_assisted);
+
+        self.tries += 1;
+        if self.answer_match.is_some() {
+            self.finish(true);
+        } else if self.tries >= self.settings.max_tries {
+            self.finish(false);
+        } else {
+            self.answer.clear();
+        }
+    }
+
+    fn render_shape(&mut self, ui: &mut Ui, country_code: &str) {
+        let theme = MapTheme::get(ui.ctx());
+        if self.shape.is_none() {
+            self.shape = CountryShape::build(country_code, self.rotation, &self.settings);
+        }
+        let Some(shape) = &mut self.shape else {
+            return;
+        };
+        shape.set_color(theme.land);
+
+        let mut scene_rect = *self.scene_rect.get_or_insert_with(|| {
+            shape
+                .bounds
+                .expand(shape.bounds.size().max_elem() * SHAPE_MARGIN)
+        });
+
+        ui.allocate_ui(SHAPE_SIZE, |ui| {
+            egui::Scene::new()
+                .zoom_range(0.1..=1000.0)
+                .show(ui, &mut scene_rect, |ui| {
+                    for mesh in &shape.meshes {
+                        ui.painter().add(Shape::Mesh(mesh.clone()));
+                    }
+
+                    let stroke = Stroke::new(pixels_to_map_units(ui, OUTLINE_WIDTH), theme.border);
+                    for outline in &shape.outlines {
+                        ui.painter().add(Shape::line(outline.clone(), stroke));
+                    }
+                });
+        });
+        self.scene_rect = Some(scene_rect);
+    }
+}
+
+/// The country's silhouette in one piece and rotated, built once per quiz and only painted on
+/// every frame.
+#[derive(Debug)]
+struct CountryShape {
+    meshes: Vec<Arc<Mesh>>,
+    outlines: Vec<Vec<Pos2>>,
+    bounds: Rect,
+    color: Color32,
+}
+
+impl CountryShape {
+    fn build(
+        country_code: &str,
+        rotation: f32,
+        settings: &CountryShapeQuizSettings,
+    ) -> Option<Self> {
+        let data = get_data();
+        let visible_polygons = visible_polygons(country_code, settings.hide_small_islands);
+
+        let outlines: Vec<Vec<Pos2>> = data
+            .get_country_outlines(country_code)
+            .map(|outlines| select(outlines, &visible_polygons))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|shape| match shape {
+                Shape::Path(path) => path.points.clone(),
+                _ => Vec::new(),
+            })
+            .collect();
+        let shifts = longitude_shifts(&outlines);
+        let outlines: Vec<Vec<Pos2>> = outlines
+            .into_iter()
+            .zip(&shifts)
+            .map(|(outline, shift)| outline.into_iter().map(|p| shift.apply(p)).collect())
+            .collect();
+
+        let all_points = outlines.concat();
+        if all_points.is_empty() {
+            return None;
+        }
+        let center = Rect::from_points(&all_points).center();
+        let rotate = |point: Pos2| rotate_around(point, center, rotation);
+
+        let meshes = if settings.outline_only {
+            Vec::new()
+        } else {
+            visible_polygons
+                .iter()
+                .zip(&shifts)
+                .filter_map(|(index, shift)| {
+                    let mut mesh = data.get_world_mesh(0).polygon_mesh(country_code, *index)?;
+                    for vertex in &mut mesh.vertices {
+                        vertex.pos = rotate(shift.apply(vertex.pos));
+                    }
+                    Some(Arc::new(mesh))
+                })
+                .collect()
+        };
+        let outlines: Vec<Vec<Pos2>> = outlines
+            .into_iter()
+            .map(|outline| outline.into_iter().map(rotate).collect())
+            .collect();
+
+        Some(Self {
+            meshes,
+            bounds: Rect::from_points(&outlines.concat()),
+            outlines,
+            color: Color32::TRANSPARENT,
+        })
+    }
+
+    /// Colours the meshes, only when the theme's land colour changed since the last frame.
+    fn set_color(&mut self, color: Color32) {
+        if self.color == color {
+            return;
+        }
+        self.color = color;
+        for mesh in &mut self.meshes {
+            for vertex in &mut Arc::make_mut(mesh).vertices {
+                vertex.color = color;
+            }
+        }
+    }
+}
+
+/// Indices of the country's polygons that should be drawn.
+fn visible_polygons(country_code: &str, hide_small_islands: bool) -> Vec<usize> {
+    let Some(country) = get_data().get_country(country_code).cloned() else {
+        return Vec::new();
+    };
+
+    let areas: Vec<f64> = country
+        .polygons
+        .iter()
+        .map(|polygon| polygon.unsigned_area() as f64)
+        .collect();
+    let largest_area = areas.iter().copied().fold(0.0, f64::max);
+
+    areas
+        .iter()
+        .enumerate()
+        .filter(|(_, area)| !hide_small_islands || **area >= largest_area * SMALL_ISLAND_RATIO)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// How a polygon is moved so a country crossing the antimeridian is drawn in one piece.
+#[derive(Debug, Default, Clone, Copy)]
+struct LongitudeShift {
+    /// The polygon itself crosses the antimeridian, its western points move east by 360°.
+    split: bool,
+    offset: f32,
+}
+
+impl LongitudeShift {
+    fn apply(&self, point: Pos2) -> Pos2 {
+        let x = if self.split && point.x < 0.0 {
+            point.x + 360.0
+        } else {
+            point.x
+        };
+        Pos2::new(x + self.offset, point.y)
+    }
+}
+
+/// Shifts for every polygon, moving the western ones east by 360° when that makes the country
+/// narrower, e.g. for Russia, Fiji or Kiribati.
+fn longitude_shifts(outlines: &[Vec<Pos2>]) -> Vec<LongitudeShift> {
+    let mut shifts: Vec<LongitudeShift> = outlines
+        .iter()
+        .map(|outline| LongitudeShift {
+            split: longitude_span(outline.iter().map(|p| p.x)) > 180.0,
+            offset: 0.0,
+        })
+        .collect();
+
+    let centers: Vec<f32> = outlines
+        .iter()
+        .zip(&shifts)
+        .map(|(outline, shift)| {
+            let xs = outline.iter().map(|p| shift.apply(*p).x);
+            let (min, max) = xs.fold((f32::MAX, f32::MIN), |(min, max), x| {
+                (min.min(x), max.max(x))
+            });
+            (min + max) / 2.0
+        })
+        .collect();
+
+    let span = |offset_west: f32| {
+        longitude_span(outlines.iter().zip(&shifts).zip(&centers).flat_map(
+            |((outline, shift), center)| {
+                let offset = if *center < 0.0 { offset_west } else { 0.0 };
+                outline.iter().map(move |p| shift.apply(*p).x + offset)
+            },
+        ))
+    };
+
+    if span(360.0) < span(0.0) {
+        for (shift, center) in shifts.iter_mut().zip(&centers) {
+            if *center < 0.0 {
+                shift.offset = 360.0;
+            }
+        }
+    }
+    shifts
+}
+
+fn longitude_span(longitudes: impl Iterator<Item = f32>) -> f32 {
+    let (min, max) = longitudes.fold((f32::MAX, f32::MIN), |(min, max), x| {
+        (min.min(x), max.max(x))
+    });
+    (max - min).max(0.0)
+}
+
+fn select<'a>(shapes: &'a [Shape], indices: &[usize]) -> Vec<&'a Shape> {
+    indices
+        .iter()
+        .filter_map(|index| shapes.get(*index))
+        .collect()
+}
+
+fn rotate_around(point: Pos2, center: Pos2, angle: f32) -> Pos2 {
+    let (sin, cos) = angle.sin_cos();
+    let offset = point - center;
+    center
+        + Vec2::new(
+            offset.x * cos - offset.y * sin,
+            offset.x * sin + offset.y * cos,
+        )
+}
+
+impl QuizTrait for CountryShapeQuiz {
+    fn render(&mut self, ui: &mut Ui) -> Option<bool> {
+        let country_code = self.solution.clone()?;
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(40.0);
+
+            ui.label(RichText::new("What country has this shape?").size(30.0));
+            ui.add_space(10.0);
+
+            self.render_shape(ui, &country_code);
+
+            match self.success {
+                None => {
+                    ui.add_space(20.0);
+
+                    let autocomplete = CountryAutocomplete::new("country_shape_answer")
+                        .enabled(self.settings.allow_autocomplete)
+                        .draw(ui, &mut self.answer);
+                    autocomplete.update_assisted(&mut self.answer_assisted);
+                    let text_edit_response = autocomplete.response;
+                    if text_edit_response.lost_focus()
+                        && ui.input_mut(|input| input.key_pressed(Key::Enter))
+                    {
+                        self.submit(&country_code);
+                    }
+                    text_edit_response.request_focus();
+
+                    ui.add_space(5.0);
+
+                    ui.columns(2, |columns| {
+                        columns[0].allocate_ui_with_layout(
+                            Vec2::ZERO,
+                            Layout::right_to_left(Align::Center),
+                            |ui| {
+                                if ui.button("Submit").clicked() {
+                                    self.submit(&country_code);
+                                }
+                                if self.settings.max_tries > 1 {
+                                    ui.label(format!("Tries left: {}", self.tries_left()));
+                                }
+                            },
+                        );
+                        columns[1].allocate_ui_with_layout(
+                            Vec2::ZERO,
+                            Layout::left_to_right(Align::Center),
+                            |ui| {
+                                if ui.button("Dunno").clicked() {
+                                    self.finish(false);
+                                }
+                            },
+                        );
+                    });
+                }
+                Some(true) => {
+                    ui.add_space(20.0);
+                    ui.label("Correct!");
+                }
+                Some(false) => {
+                    let country = get_data().get_country(&country_code).unwrap().clone();
+                    ui.add_space(20.0);
+                    ui.label(format!("Solution: {}", country.common_name));
+                }
+            }
+        });
+
+        self.success
+    }
+
+    fn start(&mut self, scheduler: &ReviewScheduler) {
+        self.reset();
+        self.started_at = Some(timestamp_ms());
+
+        let mut rng = rand::rng();
+        self.solution = scheduler.pick(
+            QuizType::CountryShape,
+            &self.settings.region_filter.country_codes(),
+        );
+
+        if self.settings.random_rotation {
+            self.rotation = rng.random_range(0.0..TAU);
+        }
+    }
+
+    fn finish(&mut self, success: bool) {
+        self.success = Some(success);
+        self.finished_at = Some(timestamp_ms());
+    }
+
+    fn has_started(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    fn is_successful(&self) -> Option<bool> {
+        self.success
+    }
+
+    fn reset(&mut self) {
+        self.success = None;
+        self.started_at = None;
+        self.solution = None;
+        self.answers.clear();
+        self.rotation = 0.0;
+        self.answer = String::new();
+        self.tries = 0;
+        self.answer_match = None;
+        self.answer_assisted = false;
+        self.assisted = false;
+        self.scene_rect = None;
+        self.shape = None;
+    }
+
+    fn collect_stats(&self) -> Option<QuizStats> {
+        let common = QuizStatsCommon {
+            started_at_ms: self.started_at?,
+            finished_at_ms: self.finished_at?,
+            subject: self.solution.clone(),
+            success: self.success,
+            attempts: self.answers.len() as u32,
+            answers: self.answers.clone(),
+        };
+
+        let stats = CountryShapeQuizStats {
+            common,
+            answer_match: self.answer_match,
+            assisted: self.assisted,
+        };
+
+        Some(QuizStats::CountryShape(stats))
+    }
+}
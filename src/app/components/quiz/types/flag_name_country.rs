@@ -1,23 +1,45 @@
+use crate::app::components::country_autocomplete::CountryAutocomplete;
+use crate::app::components::quiz::answer_matching::{match_country_name, AnswerMatch};
+use crate::app::components::quiz::distractors::pick_distractors;
+use crate::app::components::quiz::scheduler::ReviewScheduler;
 use crate::app::components::quiz::settings::flag_name_country::FlagNameCountryQuizSettings;
 use crate::app::components::quiz::stats::flag_name_country::FlagNameCountryQuizStats;
 use crate::app::components::quiz::stats::{QuizStats, QuizStatsCommon};
-use crate::app::components::quiz::QuizTrait;
+use crate::app::components::quiz::{QuizTrait, QuizType};
 use crate::app::persistence::persistent_object::PersistentObject;
 use crate::get_data;
 use crate::utils::time::timestamp_ms;
 use eframe::emath::Vec2;
 use egui::{Align, Key, Layout, RichText, Ui};
-use rand::seq::IndexedRandom;
+use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
 
+const CHOICE_KEYS: [Key; 6] = [
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+];
+const CHOICE_WIDTH: f32 = 250.0;
+const CHOICE_TEXT_SIZE: f32 = 18.0;
+const CHOICE_SPACING: f32 = 8.0;
+
 #[derive(Debug, Default)]
 pub struct FlagNameCountryQuiz {
     success: Option<bool>,
     started_at: Option<u128>,
     finished_at: Option<u128>,
     solution: Option<String>,
+    answers: Vec<String>,
     answer: String,
+    answer_assisted: bool,
+    choices: Vec<String>,
+    wrong_choices: Vec<String>,
     tries: u8,
+    answer_match: Option<AnswerMatch>,
+    assisted: bool,
     settings: FlagNameCountryQuizSettings,
 }
 
@@ -27,8 +49,18 @@ pub struct FlagNameCountryQuizState {
     started_at: Option<u128>,
     finished_at: Option<u128>,
     solution: Option<String>,
+    #[serde(default)]
+    answers: Vec<String>,
     answer: String,
+    #[serde(default)]
+    choices: Vec<String>,
+    #[serde(default)]
+    wrong_choices: Vec<String>,
     tries: u8,
+    #[serde(default)]
+    answer_match: Option<AnswerMatch>,
+    #[serde(default)]
+    assisted: bool,
     settings: FlagNameCountryQuizSettings,
 }
 
@@ -41,8 +73,13 @@ impl PersistentObject for FlagNameCountryQuiz {
             started_at: self.started_at,
             finished_at: self.finished_at,
             solution: self.solution.clone(),
+            answers: self.answers.clone(),
             answer: self.answer.clone(),
+            choices: self.choices.clone(),
+            wrong_choices: self.wrong_choices.clone(),
             tries: self.tries,
+            answer_match: self.answer_match,
+            assisted: self.assisted,
             settings: self.settings.clone(),
         }
     }
@@ -53,8 +90,14 @@ impl PersistentObject for FlagNameCountryQuiz {
             started_at: state.started_at,
             finished_at: state.finished_at,
             solution: state.solution,
+            answers: state.answers,
             answer: state.answer,
+            answer_assisted: false,
+            choices: state.choices,
+            wrong_choices: state.wrong_choices,
             tries: state.tries,
+            answer_match: state.answer_match,
+            assisted: state.assisted,
             settings: state.settings,
         }
     }
@@ -73,16 +116,117 @@ impl FlagNameCountryQuiz {
     }
 
     fn submit(&mut self, country_code: &str) {
-        let country = get_data().get_country(country_code).cloned().unwrap();
-        let right_answer = self.answer.to_lowercase() == country.common_name.to_lowercase();
+        self.answers.push(self.answer.trim().to_string());
+        self.answer_match =
+            match_country_name(&self.answer, country_code, &self.settings.answer_matching);
+        self.assisted = self.answer_match.is_some() && std::mem::take(&mut self.answer_assisted);
+
+        self.register_try(self.answer_match.is_some());
+        if self.success.is_none() {
+            self.answer.clear();
+        }
+    }
 
+    fn submit_choice(&mut self, country_code: &str, choice: String) {
+        self.answers.push(choice.clone());
+        let right_answer = choice == country_code;
+        if !right_answer {
+            self.wrong_choices.push(choice);
+        }
+        self.register_try(right_answer);
+    }
+
+    fn register_try(&mut self, right_answer: bool) {
         self.tries += 1;
         if right_answer {
             self.finish(true);
         } else if self.tries >= self.settings.max_tries {
             self.finish(false);
-        } else {
-            self.answer.clear();
+        }
+    }
+
+    fn render_text_input(&mut self, ui: &mut Ui, country_code: &str) {
+        let autocomplete = CountryAutocomplete::new("flag_name_country_answer")
+            .enabled(self.settings.allow_autocomplete)
+            .draw(ui, &mut self.answer);
+        autocomplete.update_assisted(&mut self.answer_assisted);
+        let text_edit_response = autocomplete.response;
+        if text_edit_response.lost_focus() && ui.input_mut(|input| input.key_pressed(Key::Enter)) {
+            self.submit(country_code);
+        }
+        text_edit_response.request_focus();
+
+        ui.add_space(5.0);
+
+        ui.columns(2, |columns| {
+            columns[0].allocate_ui_with_layout(
+                Vec2::ZERO,
+                Layout::right_to_left(Align::Center),
+                |ui| {
+                    if ui.button("Submit").clicked() {
+                        self.submit(country_code);
+                    }
+                    if self.settings.max_tries > 1 {
+                        ui.label(format!("Tries left: {}", self.tries_left()));
+                    }
+                },
+            );
+            columns[1].allocate_ui_with_layout(
+                Vec2::ZERO,
+                Layout::left_to_right(Align::Center),
+                |ui| {
+                    if ui.button("Dunno").clicked() {
+                        self.finish(false);
+                    }
+                },
+            );
+        });
+    }
+
+    fn render_choices(&mut self, ui: &mut Ui, country_code: &str) {
+        let mut picked_choice = None;
+
+        egui::Grid::new("flag_name_country_choices")
+            .num_columns(2)
+            .spacing(Vec2::splat(CHOICE_SPACING))
+            .show(ui, |ui| {
+                for (index, choice) in self.choices.iter().enumerate() {
+                    let name = get_data()
+                        .get_country(choice)
+                        .map(|country| country.common_name.clone())
+                        .unwrap_or_else(|| choice.clone());
+                    let enabled = !self.wrong_choices.contains(choice);
+
+                    let button = egui::Button::new(
+                        RichText::new(format!("{}. {}", index + 1, name)).size(CHOICE_TEXT_SIZE),
+                    )
+                    .min_size(Vec2::new(CHOICE_WIDTH, 0.0));
+                    let key_pressed = CHOICE_KEYS
+                        .get(index)
+                        .is_some_and(|key| ui.input(|input| input.key_pressed(*key)));
+
+                    if ui.add_enabled(enabled, button).clicked() || (enabled && key_pressed) {
+                        picked_choice = Some(choice.clone());
+                    }
+
+                    if index % 2 == 1 {
+                        ui.end_row();
+                    }
+                }
+            });
+
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            if ui.button("Dunno").clicked() {
+                self.finish(false);
+            }
+            if self.settings.max_tries > 1 {
+                ui.label(format!("Tries left: {}", self.tries_left()));
+            }
+        });
+
+        if let Some(choice) = picked_choice {
+            self.submit_choice(country_code, choice);
         }
     }
 }
@@ -108,40 +252,11 @@ impl QuizTrait for FlagNameCountryQuiz {
             match self.success {
                 None => {
                     ui.add_space(20.0);
-
-                    let text_edit_response = ui.text_edit_singleline(&mut self.answer);
-                    if text_edit_response.lost_focus()
-                        && ui.input_mut(|input| input.key_pressed(Key::Enter))
-                    {
-                        self.submit(&country_code);
+                    if self.choices.is_empty() {
+                        self.render_text_input(ui, &country_code);
+                    } else {
+                        self.render_choices(ui, &country_code);
                     }
-                    text_edit_response.request_focus();
-
-                    ui.add_space(5.0);
-
-                    ui.columns(2, |columns| {
-                        columns[0].allocate_ui_with_layout(
-                            Vec2::ZERO,
-                            Layout::right_to_left(Align::Center),
-                            |ui| {
-                                if ui.button("Submit").clicked() {
-                                    self.submit(&country_code);
-                                }
-                                if self.settings.max_tries > 1 {
-                                    ui.label(format!("Tries left: {}", self.tries_left()));
-                                }
-                            },
-                        );
-                        columns[1].allocate_ui_with_layout(
-                            Vec2::ZERO,
-                            Layout::left_to_right(Align::Center),
-                            |ui| {
-                                if ui.button("Dunno").clicked() {
-                                    self.finish(false);
-                                }
-                            },
-                        );
-                    });
                 }
                 Some(true) => {
                     ui.add_space(20.0);
@@ -158,17 +273,28 @@ impl QuizTrait for FlagNameCountryQuiz {
         self.success
     }
 
-    fn start(&mut self) {
+    fn start(&mut self, scheduler: &ReviewScheduler) {
         self.reset();
         self.started_at = Some(timestamp_ms());
 
         let mut rng = rand::rng();
-        let random_country_code = get_data()
-            .get_country_codes()
-            .choose(&mut rng)
-            .unwrap()
-            .clone();
-        self.solution = Some(random_country_code);
+        let country_codes = self.settings.region_filter.country_codes();
+        self.solution = scheduler.pick(QuizType::FlagNameCountry, &country_codes);
+        let Some(country_code) = &self.solution else {
+            return;
+        };
+
+        if let Some(choice_count) = self.settings.answer_mode.choice_count() {
+            let mut choices = pick_distractors(
+                country_code,
+                &country_codes,
+                choice_count - 1,
+                self.settings.distractor_bias,
+            );
+            choices.push(country_code.clone());
+            choices.shuffle(&mut rng);
+            self.choices = choices;
+        }
     }
 
     fn finish(&mut self, success: bool) {
@@ -188,17 +314,31 @@ impl QuizTrait for FlagNameCountryQuiz {
         self.success = None;
         self.started_at = None;
         self.solution = None;
+        self.answers.clear();
         self.answer = String::new();
+        self.choices.clear();
+        self.wrong_choices.clear();
         self.tries = 0;
+        self.answer_match = None;
+        self.answer_assisted = false;
+        self.assisted = false;
     }
 
     fn collect_stats(&self) -> Option<QuizStats> {
         let common = QuizStatsCommon {
             started_at_ms: self.started_at?,
             finished_at_ms: self.finished_at?,
+            subject: self.solution.clone(),
+            success: self.success,
+            attempts: self.answers.len() as u32,
+            answers: self.answers.clone(),
         };
 
-        let stats = FlagNameCountryQuizStats { common };
+        let stats = FlagNameCountryQuizStats {
+            common,
+            answer_match: self.answer_match,
+            assisted: self.assisted,
+        };
 
         Some(QuizStats::FlagNameCountry(stats))
     }
@@ -0,0 +1,230 @@
+use crate::app::components::quiz::scheduler::ReviewScheduler;
+use crate::app::components::quiz::settings::locate_country::LocateCountryQuizSettings;
+use crate::app::components::quiz::stats::locate_country::LocateCountryQuizStats;
+use crate::app::components::quiz::stats::{QuizStats, QuizStatsCommon};
+use crate::app::components::quiz::{QuizTrait, QuizType};
+use crate::app::components::world_map::{WorldMapState, WorldMapStatePersist};
+use crate::app::persistence::persistent_object::PersistentObject;
+use crate::get_data;
+use crate::utils::time::timestamp_ms;
+use egui::{Color32, RichText, Ui, Vec2};
+use serde::{Deserialize, Serialize};
+
+const WRONG_GUESS_COLOR: Color32 = Color32::from_rgb(214, 69, 65);
+const SOLUTION_COLOR: Color32 = Color32::from_rgb(88, 180, 98);
+const MAP_BOTTOM_SPACE: f32 = 40.0;
+
+#[derive(Debug)]
+pub struct LocateCountryQuiz {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    solution: Option<String>,
+    answers: Vec<String>,
+    wrong_guesses: Vec<String>,
+    tries: u8,
+    settings: LocateCountryQuizSettings,
+    world_map: WorldMapState,
+}
+
+impl Default for LocateCountryQuiz {
+    fn default() -> Self {
+        Self {
+            success: None,
+            started_at: None,
+            finished_at: None,
+            solution: None,
+            answers: Vec::new(),
+            wrong_guesses: Vec::new(),
+            tries: 0,
+            settings: LocateCountryQuizSettings::default(),
+            world_map: WorldMapState::for_quiz(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LocateCountryQuizState {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    solution: Option<String>,
+    #[serde(default)]
+    answers: Vec<String>,
+    wrong_guesses: Vec<String>,
+    tries: u8,
+    settings: LocateCountryQuizSettings,
+    world_map: WorldMapStatePersist,
+}
+
+impl PersistentObject for LocateCountryQuiz {
+    type PersistentState = LocateCountryQuizState;
+
+    fn save_state(&self) -> Self::PersistentState {
+        LocateCountryQuizState {
+            success: self.success,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            solution: self.solution.clone(),
+            answers: self.answers.clone(),
+            wrong_guesses: self.wrong_guesses.clone(),
+            tries: self.tries,
+            settings: self.settings.clone(),
+            world_map: self.world_map.save_state(),
+        }
+    }
+
+    fn load_state(state: Self::PersistentState) -> Self {
+        Self {
+            success: state.success,
+            started_at: state.started_at,
+            finished_at: state.finished_at,
+            solution: state.solution,
+            answers: state.answers,
+            wrong_guesses: state.wrong_guesses,
+            tries: state.tries,
+            settings: state.settings,
+            world_map: WorldMapState::load_for_quiz(state.world_map),
+        }
+    }
+}
+
+impl LocateCountryQuiz {
+    pub fn with_settings(settings: LocateCountryQuizSettings) -> Self {
+        Self {
+            settings,
+            ..Self::default()
+        }
+    }
+
+    fn tries_left(&self) -> u8 {
+        self.settings.max_tries - self.tries
+    }
+
+    fn submit(&mut self, country_code: &str, clicked_country: String) {
+        self.answers.push(clicked_country.clone());
+        self.tries += 1;
+        if clicked_country == country_code {
+            self.finish(true);
+            return;
+        }
+
+        if !self.wrong_guesses.contains(&clicked_country) {
+            self.wrong_guesses.push(clicked_country);
+        }
+
+        if self.tries >= self.settings.max_tries {
+            self.finish(false);
+            self.world_map.focus_country(country_code);
+        }
+    }
+
+    fn update_highlights(&mut self, country_code: &str) {
+        self.world_map.clear_highlights();
+        for wrong_guess in &self.wrong_guesses {
+            self.world_map
+                .highlight_country(wrong_guess, WRONG_GUESS_COLOR);
+        }
+        if self.success.is_some() {
+            self.world_map
+                .highlight_country(country_code, SOLUTION_COLOR);
+        }
+    }
+}
+
+impl QuizTrait for LocateCountryQuiz {
+    fn render(&mut self, ui: &mut Ui) -> Option<bool> {
+        let country_code = self.solution.clone()?;
+        let country = get_data().get_country(&country_code).cloned()?;
+
+        self.update_highlights(&country_code);
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(10.0);
+            ui.label(RichText::new(format!("Where is {}?", country.common_name)).size(30.0));
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| match self.success {
+                None => {
+                    if ui.button("Dunno").clicked() {
+                        self.finish(false);
+                        self.world_map.focus_country(&country_code);
+                    }
+                    if self.settings.max_tries > 1 {
+                        ui.label(format!("Tries left: {}", self.tries_left()));
+                    }
+                }
+                Some(true) => {
+                    ui.label("Correct!");
+                }
+                Some(false) => {
+                    ui.label(format!("Solution: {}", country.common_name));
+                }
+            });
+            ui.add_space(5.0);
+
+            let map_size = Vec2::new(
+                ui.available_width(),
+                (ui.available_height() - MAP_BOTTOM_SPACE).max(0.0),
+            );
+            let map_response = ui.allocate_ui(map_size, |ui| self.world_map.draw(ui)).inner;
+
+            if self.success.is_none()
+                && let Some(clicked_country) = map_response.clicked_country
+            {
+                self.submit(&country_code, clicked_country);
+            }
+        });
+
+        self.success
+    }
+
+    fn start(&mut self, scheduler: &ReviewScheduler) {
+        self.reset();
+        self.started_at = Some(timestamp_ms());
+
+        self.solution = scheduler.pick(
+            QuizType::LocateCountry,
+            &self.settings.region_filter.country_codes(),
+        );
+    }
+
+    fn finish(&mut self, success: bool) {
+        self.success = Some(success);
+        self.finished_at = Some(timestamp_ms());
+    }
+
+    fn has_started(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    fn is_successful(&self) -> Option<bool> {
+        self.success
+    }
+
+    fn reset(&mut self) {
+        self.success = None;
+        self.started_at = None;
+        self.solution = None;
+        self.answers.clear();
+        self.wrong_guesses.clear();
+        self.tries = 0;
+        self.world_map.clear_highlights();
+        self.world_map.reset_view();
+    }
+
+    fn collect_stats(&self) -> Option<QuizStats> {
+        let common = QuizStatsCommon {
+            started_at_ms: self.started_at?,
+            finished_at_ms: self.finished_at?,
+            subject: self.solution.clone(),
+            success: self.success,
+            attempts: self.answers.len() as u32,
+            answers: self.answers.clone(),
+        };
+
+        let stats = LocateCountryQuizStats { common };
+
+        Some(QuizStats::LocateCountry(stats))
+    }
+}
@@ -0,0 +1,334 @@
+use crate::app::components::country_autocomplete::CountryAutocomplete;
+use crate::app::components::quiz::answer_matching::{
+    match_country_among, AnswerMatch, AnswerMatching,
+};
+use crate::app::components::quiz::scheduler::ReviewScheduler;
+use crate::app::components::quiz::settings::world_countries::WorldCountriesQuizSettings;
+use crate::app::components::quiz::stats::world_countries::WorldCountriesQuizStats;
+use crate::app::components::quiz::stats::{QuizStats, QuizStatsCommon};
+use crate::app::components::quiz::QuizTrait;
+use crate::app::components::world_map::{WorldMapState, WorldMapStatePersist};
+use crate::app::persistence::persistent_object::PersistentObject;
+use crate::utils::time::timestamp_ms;
+use egui::{Color32, Key, RichText, Ui, Vec2};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+const FOUND_COLOR: Color32 = Color32::from_rgb(88, 180, 98);
+const MISSED_COLOR: Color32 = Color32::from_rgb(214, 69, 65);
+const MAP_BOTTOM_SPACE: f32 = 40.0;
+/// Longer gaps between two frames of the quiz count as a pause, e.g. when switching views.
+const MAX_TICK: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub struct WorldCountriesQuiz {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    found_countries: Vec<String>,
+    answers: Vec<String>,
+    fuzzy_matches: Vec<String>,
+    assisted_answers: Vec<String>,
+    answer: String,
+    answer_assisted: bool,
+    elapsed_ms: u128,
+    last_tick: Option<Instant>,
+    settings: WorldCountriesQuizSettings,
+    world_map: WorldMapState,
+}
+
+impl Default for WorldCountriesQuiz {
+    fn default() -> Self {
+        Self {
+            success: None,
+            started_at: None,
+            finished_at: None,
+            found_countries: Vec::new(),
+            answers: Vec::new(),
+            fuzzy_matches: Vec::new(),
+            assisted_answers: Vec::new(),
+            answer: String::new(),
+            answer_assisted: false,
+            elapsed_ms: 0,
+            last_tick: None,
+            settings: WorldCountriesQuizSettings::default(),
+            world_map: WorldMapState::for_quiz(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WorldCountriesQuizState {
+    success: Option<bool>,
+    started_at: Option<u128>,
+    finished_at: Option<u128>,
+    found_countries: Vec<String>,
+    #[serde(default)]
+    answers: Vec<String>,
+    #[serde(default)]
+    fuzzy_matches: Vec<String>,
+    #[serde(default)]
+    assisted_answers: Vec<String>,
+    answer: String,
+    elapsed_ms: u128,
+    settings: WorldCountriesQuizSettings,
+    world_map: WorldMapStatePersist,
+}
+
+impl PersistentObject for WorldCountriesQuiz {
+    type PersistentState = WorldCountriesQuizState;
+
+    fn save_state(&self) -> Self::PersistentState {
+        WorldCountriesQuizState {
+            success: self.success,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            found_countries: self.found_countries.clone(),
+            answers: self.answers.clone(),
+            fuzzy_matches: self.fuzzy_matches.clone(),
+            assisted_answers: self.assisted_answers.clone(),
+            answer: self.answer.clone(),
+            elapsed_ms: self.elapsed_ms,
+            settings: self.settings.clone(),
+            world_map: self.world_map.save_state(),
+        }
+    }
+
+    fn load_state(state: Self::PersistentState) -> Self {
+        Self {
+            success: state.success,
+            started_at: state.started_at,
+            finished_at: state.finished_at,
+            found_countries: state.found_countries,
+            answers: state.answers,
+            fuzzy_matches: state.fuzzy_matches,
+            assisted_answers: state.assisted_answers,
+            answer: state.answer,
+            answer_assisted: false,
+            elapsed_ms: state.elapsed_ms,
+            last_tick: None,
+            settings: state.settings,
+            world_map: WorldMapState::load_for_quiz(state.world_map),
+        }
+    }
+}
+
+impl WorldCountriesQuiz {
+    pub fn with_settings(settings: WorldCountriesQuizSettings) -> Self {
+        Self {
+            settings,
+            ..Self::default()
+        }
+    }
+
+    fn duration_ms(&self) -> u128 {
+        self.settings.duration_minutes as u128 * 60 * 1000
+    }
+
+    fn remaining_ms(&self) -> u128 {
+        self.duration_ms().saturating_sub(self.elapsed_ms)
+    }
+
+    fn missed_countries(&self) -> Vec<String> {
+        self.settings
+            .region_filter
+            .country_codes()
+            .into_iter()
+            .filter(|code| !self.found_countries.contains(code))
+            .collect()
+    }
+
+    fn tick(&mut self) {
+        let now = Instant::now();
+        if let Some(last_tick) = self.last_tick {
+            self.elapsed_ms += now.duration_since(last_tick).min(MAX_TICK).as_millis();
+        }
+        self.last_tick = Some(now);
+
+        if self.remaining_ms() == 0 {
+            self.finish(false);
+        }
+    }
+
+    /// Accepts the current answer as soon as it exactly names a country that wasn't found yet.
+    /// Typos and ISO codes are only considered once the answer is submitted, otherwise "Nige" or
+    /// "CH" would be taken before "Nigeria" or "Chile" are typed out.
+    fn check_answer(&mut self, submitted: bool) {
+        let matching = if submitted {
+            self.settings.answer_matching
+        } else {
+            AnswerMatching {
+                accept_iso_codes: false,
+                typo_tolerance: 0,
+                ..self.settings.answer_matching
+            }
+        };
+
+        let Some((country_code, answer_match)) =
+            match_country_among(&self.answer, &self.missed_countries(), &matching)
+        else {
+            return;
+        };
+
+        if answer_match == AnswerMatch::Fuzzy {
+            self.fuzzy_matches.push(country_code.clone());
+        }
+        if std::mem::take(&mut self.answer_assisted) {
+            self.assisted_answers.push(country_code.clone());
+        }
+        self.answers.push(self.answer.trim().to_string());
+        self.found_countries.push(country_code);
+        self.answer.clear();
+        if self.missed_countries().is_empty() {
+            self.finish(true);
+        }
+    }
+
+    fn update_highlights(&mut self) {
+        self.world_map.clear_highlights();
+        for country_code in &self.found_countries {
+            self.world_map.highlight_country(country_code, FOUND_COLOR);
+        }
+        if self.success.is_some() {
+            for country_code in self.missed_countries() {
+                self.world_map
+                    .highlight_country(&country_code, MISSED_COLOR);
+            }
+        }
+    }
+}
+
+impl QuizTrait for WorldCountriesQuiz {
+    fn render(&mut self, ui: &mut Ui) -> Option<bool> {
+        if self.success.is_none() {
+            self.tick();
+            ui.ctx().request_repaint_after(Duration::from_millis(200));
+        }
+
+        self.update_highlights();
+        let total = self.settings.region_filter.country_codes().len();
+        let title = if self.settings.region_filter.is_whole_world() {
+            "Name every country in the world!"
+        } else {
+            "Name every country in the selected regions!"
+        };
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(10.0);
+            ui.label(RichText::new(title).size(30.0));
+            ui.add_space(5.0);
+
+            let remaining_seconds = self.remaining_ms() / 1000;
+            ui.label(
+                RichText::new(format!(
+                    "{:02}:{:02}  |  {} / {}",
+                    remaining_seconds / 60,
+                    remaining_seconds % 60,
+                    self.found_countries.len(),
+                    total
+                ))
+                .size(20.0),
+            );
+            ui.add_space(5.0);
+
+            match self.success {
+                None => {
+                    ui.horizontal(|ui| {
+                        let autocomplete = CountryAutocomplete::new("world_countries_answer")
+                            .enabled(self.settings.allow_autocomplete)
+                            .draw(ui, &mut self.answer);
+                        autocomplete.update_assisted(&mut self.answer_assisted);
+                        let text_edit_response = autocomplete.response;
+                        if text_edit_response.changed() {
+                            self.check_answer(false);
+                        }
+                        if text_edit_response.lost_focus()
+                            && ui.input(|input| input.key_pressed(Key::Enter))
+                        {
+                            self.check_answer(true);
+                        }
+                        text_edit_response.request_focus();
+
+                        if ui.button("Give up").clicked() {
+                            self.finish(false);
+                        }
+                    });
+                }
+                Some(true) => {
+                    ui.label("You named every country!");
+                }
+                Some(false) => {
+                    ui.label(format!(
+                        "Missed {} countries, they are marked on the map.",
+                        total - self.found_countries.len()
+                    ));
+                }
+            }
+            ui.add_space(5.0);
+
+            let map_size = Vec2::new(
+                ui.available_width(),
+                (ui.available_height() - MAP_BOTTOM_SPACE).max(0.0),
+            );
+            ui.allocate_ui(map_size, |ui| self.world_map.draw(ui));
+        });
+
+        self.success
+    }
+
+    fn start(&mut self, _scheduler: &ReviewScheduler) {
+        self.reset();
+        self.started_at = Some(timestamp_ms());
+    }
+
+    fn finish(&mut self, success: bool) {
+        self.success = Some(success);
+        self.finished_at = Some(timestamp_ms());
+        self.last_tick = None;
+    }
+
+    fn has_started(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    fn is_successful(&self) -> Option<bool> {
+        self.success
+    }
+
+    fn reset(&mut self) {
+        self.success = None;
+        self.started_at = None;
+        self.found_countries.clear();
+        self.answers.clear();
+        self.fuzzy_matches.clear();
+        self.answer_assisted = false;
+        self.assisted_answers.clear();
+        self.answer = String::new();
+        self.elapsed_ms = 0;
+        self.last_tick = None;
+        self.world_map.clear_highlights();
+        self.world_map.reset_view();
+    }
+
+    fn collect_stats(&self) -> Option<QuizStats> {
+        let common = QuizStatsCommon {
+            started_at_ms: self.started_at?,
+            finished_at_ms: self.finished_at?,
+            subject: None,
+            success: self.success,
+            attempts: self.answers.len() as u32,
+            answers: self.answers.clone(),
+        };
+
+        let stats = WorldCountriesQuizStats {
+            common,
+            elapsed_ms: self.elapsed_ms,
+            found_countries: self.found_countries.clone(),
+            fuzzy_matches: self.fuzzy_matches.clone(),
+            assisted_answers: self.assisted_answers.clone(),
+            missed_countries: self.missed_countries(),
+        };
+
+        Some(QuizStats::WorldCountries(stats))
+    }
+}
@@ -64,6 +64,7 @@ impl SettingsSlider {
         self
     }
 
+    #[allow(dead_code)]
     pub fn prefix(&mut self, prefix: impl Into<String>) -> &mut Self {
         self.prefix = Some(prefix.into());
         self
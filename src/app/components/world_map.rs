@@ -1,19 +1,52 @@
+use crate::app::components::map_theme::MapTheme;
 use crate::app::persistence::persistent_object::PersistentObject;
+use crate::data::detail_levels::detail_level_for_zoom;
 use crate::data::identified_polygon::IdentifiedPolygonType;
+use crate::data::meshes::ProjectedWorldMesh;
+use crate::data::projection::Projection;
 use crate::get_data;
-use egui::{Color32, Pos2, Rect, Stroke, Ui};
+use egui::epaint::{Vertex, WHITE_UV};
+use egui::{Color32, Mesh, Pos2, Rect, Shape, Stroke, Ui, Vec2};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Arc;
-use world_data::world_data_types::data::capital::Capital;
 
-const HEIGHT: f32 = 670.0;
-const WIDTH: f32 = 1010.0;
-const BACKGROUND_COLOR: Color32 = Color32::from_rgb(172, 204, 228);
 const CORNER_RADIUS: f32 = 10.0;
+const FOCUS_MARGIN: f32 = 5.0;
+const FOCUS_SPEED: f32 = 6.0;
+const UNTINTED_COLOR: Color32 = Color32::from_rgb(190, 190, 190);
+const TINT_HOVER_LIGHTEN: f32 = 0.25;
+const TINT_SELECT_LIGHTEN: f32 = 0.45;
+
+/// How countries are coloured.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum MapMode {
+    /// The land colours of the map theme.
+    #[default]
+    Political,
+    /// Every country in its own colour, countries without one are grey.
+    Tinted(HashMap<String, Color32>),
+}
+
+/// What happened on the map during the current frame.
+#[derive(Debug, Default)]
+pub struct WorldMapResponse {
+    pub clicked_country: Option<String>,
+    /// Clicked position in map coordinates (longitude, -latitude).
+    pub clicked_position: Option<Pos2>,
+}
 
 #[derive(Debug)]
 pub struct WorldMapState {
     scene_rect: Rect,
+    focus_target: Option<Rect>,
+    highlighted_countries: HashMap<String, Color32>,
+    mesh: MapMesh,
+    projection: Projection,
+    pub mode: MapMode,
+    pub capitals_enabled: bool,
+    pub selection_enabled: bool,
     pub hovered_country: Option<String>,
     pub hovered_capital: Option<String>,
     pub selected_country: Option<String>,
@@ -21,10 +54,25 @@ pub struct WorldMapState {
     pub mouse_position: Option<Pos2>,
 }
 
+fn default_scene_rect(projection: &Projection) -> Rect {
+    if projection.is_globe() {
+        projection.map_bounds().expand(FOCUS_MARGIN)
+    } else {
+        Rect::from_two_pos(Pos2::new(-180.0, -180.0), Pos2::new(180.0, 180.0))
+    }
+}
+
 impl Default for WorldMapState {
     fn default() -> Self {
         Self {
-            scene_rect: Rect::from_two_pos(Pos2::new(-180.0, -180.0), Pos2::new(180.0, 180.0)),
+            scene_rect: default_scene_rect(&Projection::default()),
+            focus_target: None,
+            highlighted_countries: HashMap::new(),
+            mesh: MapMesh::default(),
+            projection: Projection::default(),
+            mode: MapMode::default(),
+            capitals_enabled: true,
+            selection_enabled: true,
             hovered_country: None,
             hovered_capital: None,
             selected_country: None,
@@ -42,6 +90,8 @@ pub struct WorldMapStatePersist {
     scene_rect_max_y: f32,
     selected_country: Option<String>,
     selected_capital: Option<String>,
+    #[serde(default)]
+    projection: Projection,
 }
 
 impl PersistentObject for WorldMapState {
@@ -55,6 +105,7 @@ impl PersistentObject for WorldMapState {
             scene_rect_max_y: self.scene_rect.max.y,
             selected_country: self.selected_country.clone(),
             selected_capital: self.selected_capital.clone(),
+            projection: self.projection,
         }
     }
 
@@ -64,130 +115,469 @@ impl PersistentObject for WorldMapState {
         let scene_rect = Rect::from_min_max(min, max);
         Self {
             scene_rect,
-            hovered_country: None,
-            hovered_capital: None,
             selected_country: state.selected_country,
             selected_capital: state.selected_capital,
-            mouse_position: None,
+            projection: state.projection,
+            ..Self::default()
         }
     }
 }
 
 impl WorldMapState {
-    pub fn draw(&mut self, ui: &mut Ui) {
+    /// The map quizzes answer or show progress on, it hides capitals and doesn't keep a
+    /// selection.
+    pub fn for_quiz() -> Self {
+        Self::default().without_capitals_and_selection()
+    }
+
+    /// Loads a map saved from [`WorldMapState::for_quiz`].
+    pub fn load_for_quiz(state: WorldMapStatePersist) -> Self {
+        Self::load_state(state).without_capitals_and_selection()
+    }
+
+    fn without_capitals_and_selection(mut self) -> Self {
+        self.capitals_enabled = false;
+        self.selection_enabled = false;
+        self
+    }
+
+    pub fn draw(&mut self, ui: &mut Ui) -> WorldMapResponse {
+        self.draw_with_overlay(ui, |_| {})
+    }
+
+    /// Draws the map, `overlay` is painted on top of it in projected map coordinates.
+    pub fn draw_with_overlay(
+        &mut self,
+        ui: &mut Ui,
+        overlay: impl FnOnce(&mut Ui),
+    ) -> WorldMapResponse {
+        self.update_focus(ui);
+
         let scene = egui::Scene::new().zoom_range(0.2..=1000.0);
+        let mut scene_rect = self.scene_rect;
+        let mut response = WorldMapResponse::default();
 
-        scene.show(ui, &mut self.scene_rect, |ui| {
-            let hover_rect = Rect::from_two_pos(Pos2::new(-180.0, -90.0), Pos2::new(180.0, 60.0));
-            ui.painter()
-                .rect_filled(hover_rect, CORNER_RADIUS, BACKGROUND_COLOR);
+        let theme = MapTheme::get(ui.ctx());
+
+        scene.show(ui, &mut scene_rect, |ui| {
+            let detail_level = detail_level_for_zoom(1.0 / pixels_to_map_units(ui, 1.0));
+            let projection = self.projection;
+            let hover_rect = projection.map_bounds();
+            if projection.is_globe() {
+                ui.painter().circle_filled(
+                    hover_rect.center(),
+                    hover_rect.width() / 2.0,
+                    theme.water,
+                );
+            } else {
+                ui.painter()
+                    .rect_filled(hover_rect, CORNER_RADIUS, theme.water);
+            }
 
             let hover_rect_response =
                 ui.interact(hover_rect, ui.id().with("map_area"), egui::Sense::click());
-            if hover_rect_response.hovered() {
-                if let Some(mouse_pos) = hover_rect_response.hover_pos() {
-                    self.mouse_position = Some(mouse_pos);
-                    if let Some(polygon) =
-                        get_data().get_polygon_id_at_point(mouse_pos.x, -mouse_pos.y)
-                    {
-                        let id = polygon.id();
-                        match polygon.polygon_type() {
-                            IdentifiedPolygonType::Country => {
-                                self.hovered_capital = None;
-                                self.hovered_country = Some(id.to_owned());
-                            }
-                            IdentifiedPolygonType::Capital => {
-                                self.hovered_country = None;
-                                self.hovered_capital = Some(id.to_owned());
-                            }
+            let hovered_position = hover_rect_response
+                .hover_pos()
+                .filter(|_| hover_rect_response.hovered())
+                .and_then(|position| projection.unproject(position));
+            self.mouse_position =
+                hovered_position.map(|(longitude, latitude)| Pos2::new(longitude, -latitude));
+            if let Some((longitude, latitude)) = hovered_position {
+                let data = get_data();
+                let polygon = if self.capitals_enabled {
+                    data.get_polygon_id_at_point(longitude, latitude, detail_level)
+                } else {
+                    data.get_country_at_point(longitude, latitude, detail_level)
+                };
+
+                if let Some(polygon) = polygon {
+                    let id = polygon.id();
+                    match polygon.polygon_type() {
+                        IdentifiedPolygonType::Country => {
+                            self.hovered_capital = None;
+                            self.hovered_country = Some(id.to_owned());
+                        }
+                        IdentifiedPolygonType::Capital => {
+                            self.hovered_country = None;
+                            self.hovered_capital = Some(id.to_owned());
                         }
-                    } else {
-                        self.hovered_country = None;
-                        self.hovered_capital = None;
                     }
+                } else {
+                    self.hovered_country = None;
+                    self.hovered_capital = None;
                 }
+            } else if hover_rect_response.hovered() {
+                self.hovered_country = None;
+                self.hovered_capital = None;
             }
 
             if hover_rect_response.clicked() {
-                if let Some(hovered_country) = &self.hovered_country {
-                    self.selected_country =
-                        if self.selected_country.as_ref() == Some(hovered_country) {
-                            None
-                        } else {
-                            Some(hovered_country.to_owned())
-                        };
-                } else {
-                    self.selected_country = None;
-                }
-
-                if let Some(hovered_capital) = &self.hovered_capital {
-                    self.selected_capital =
-                        if self.selected_capital.as_ref() == Some(hovered_capital) {
-                            None
-                        } else {
-                            Some(hovered_capital.to_owned())
-                        };
-                } else {
-                    self.selected_capital = None;
+                response.clicked_country = self.hovered_country.clone();
+                response.clicked_position = hover_rect_response
+                    .interact_pointer_pos()
+                    .and_then(|position| projection.unproject(position))
+                    .map(|(longitude, latitude)| Pos2::new(longitude, -latitude));
+                if self.selection_enabled {
+                    self.update_selection();
                 }
             }
 
-            for country_code in get_data().get_country_codes() {
-                let is_selected = Some(country_code.to_owned()) == self.selected_country;
-                let is_hovered = Some(country_code.to_owned()) == self.hovered_country;
-                draw_country(ui, country_code, is_selected, is_hovered);
-            }
+            let data = get_data();
+            let country_colors: Vec<(&str, Color32)> = data
+                .get_country_codes()
+                .iter()
+                .map(|country_code| {
+                    (
+                        country_code.as_str(),
+                        self.country_color(&theme, country_code),
+                    )
+                })
+                .collect();
+            self.mesh.set_geometry(detail_level, projection);
+            self.mesh.update_colors(&country_colors, theme.border);
+            self.mesh.update_culling(ui.clip_rect());
+            self.mesh.draw(ui);
 
-            for (name, capital) in get_data().get_capitals() {
-                let is_selected = Some(name.to_owned()) == self.selected_capital;
-                let is_hovered = Some(name.to_owned()) == self.hovered_capital;
-                draw_capital(ui, capital, is_selected, is_hovered);
+            if self.capitals_enabled {
+                let view_rect = ui.clip_rect();
+                for (name, capital) in data.get_capitals() {
+                    let coords = capital.coordinates;
+                    let Some(position) = projection
+                        .project(coords.x, coords.y)
+                        .filter(|position| view_rect.contains(*position))
+                    else {
+                        continue;
+                    };
+
+                    let is_selected = Some(name.to_owned()) == self.selected_capital;
+                    let is_hovered = Some(name.to_owned()) == self.hovered_capital;
+                    draw_capital(ui, &theme, position, is_selected, is_hovered);
+                }
             }
+
+            overlay(ui);
         });
+        self.scene_rect = scene_rect;
+
+        response
+    }
+
+    fn country_color(&self, theme: &MapTheme, country_code: &str) -> Color32 {
+        let is_selected = self.selected_country.as_deref() == Some(country_code);
+        let is_hovered = self.hovered_country.as_deref() == Some(country_code);
+        self.highlighted_countries
+            .get(country_code)
+            .copied()
+            .or_else(|| self.tint(country_code, is_selected, is_hovered))
+            .unwrap_or(if is_selected {
+                theme.land_selected
+            } else if is_hovered {
+                theme.land_hovered
+            } else {
+                theme.land
+            })
+    }
+
+    /// Smoothly moves the view to the given country.
+    pub fn focus_country(&mut self, country_code: &str) {
+        if let Some(bounds) = get_data().get_country_bounds(country_code) {
+            self.focus_rect(bounds);
+        }
+    }
+
+    /// Smoothly moves the view to the given area in map coordinates.
+    pub fn focus_rect(&mut self, rect: Rect) {
+        if let Some(target) = self.projection.project_rect(rect) {
+            self.focus_target = Some(target.expand(FOCUS_MARGIN));
+        }
+    }
+
+    /// Smoothly moves the view back to the whole world.
+    pub fn reset_view(&mut self) {
+        self.focus_target = Some(default_scene_rect(&self.projection));
+    }
+
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    /// Switching to another kind of projection also resets the view, the old one wouldn't fit.
+    pub fn set_projection(&mut self, projection: Projection) {
+        let kind_changed = !self.projection.same_kind(&projection);
+        self.projection = projection;
+        if kind_changed {
+            self.reset_view();
+        }
+    }
+
+    pub fn highlight_country(&mut self, country_code: &str, color: Color32) {
+        self.highlighted_countries
+            .insert(country_code.to_owned(), color);
     }
-}
 
-fn draw_country(ui: &mut Ui, country_code: &str, is_selected: bool, is_hovered: bool) {
-    #[cfg(feature = "profiling")]
-    profiling::scope!("draw_country");
+    pub fn clear_highlights(&mut self) {
+        self.highlighted_countries.clear();
+    }
+
+    fn tint(&self, country_code: &str, is_selected: bool, is_hovered: bool) -> Option<Color32> {
+        let MapMode::Tinted(tints) = &self.mode else {
+            return None;
+        };
 
-    if let Some(country_meshes) = get_data().get_country_meshes(country_code) {
-        let meshes = if is_selected {
-            &country_meshes.selected
+        let color = tints.get(country_code).copied().unwrap_or(UNTINTED_COLOR);
+        let color = if is_selected {
+            color.lerp_to_gamma(Color32::WHITE, TINT_SELECT_LIGHTEN)
         } else if is_hovered {
-            &country_meshes.hovered
+            color.lerp_to_gamma(Color32::WHITE, TINT_HOVER_LIGHTEN)
         } else {
-            &country_meshes.default
+            color
         };
+        Some(color)
+    }
+
+    fn update_selection(&mut self) {
+        if let Some(hovered_country) = &self.hovered_country {
+            self.selected_country = if self.selected_country.as_ref() == Some(hovered_country) {
+                None
+            } else {
+                Some(hovered_country.to_owned())
+            };
+        } else {
+            self.selected_country = None;
+        }
 
-        for mesh in meshes {
-            ui.painter().add(mesh.clone());
+        if let Some(hovered_capital) = &self.hovered_capital {
+            self.selected_capital = if self.selected_capital.as_ref() == Some(hovered_capital) {
+                None
+            } else {
+                Some(hovered_capital.to_owned())
+            };
+        } else {
+            self.selected_capital = None;
         }
     }
 
-    if let Some(outlines) = get_data().get_country_outlines(country_code) {
-        for outline in outlines {
-            ui.painter().add(outline.clone());
+    fn update_focus(&mut self, ui: &Ui) {
+        let Some(target) = self.focus_target else {
+            return;
+        };
+
+        let dt = ui.input(|input| input.stable_dt).min(0.1);
+        let t = (FOCUS_SPEED * dt).min(1.0);
+        let min = self.scene_rect.min.lerp(target.min, t);
+        let max = self.scene_rect.max.lerp(target.max, t);
+        self.scene_rect = Rect::from_min_max(min, max);
+
+        let remaining: Vec2 = (target.min - min).abs() + (target.max - max).abs();
+        if remaining.max_elem() < 0.01 {
+            self.scene_rect = target;
+            self.focus_target = None;
+        } else {
+            ui.ctx().request_repaint();
         }
     }
 }
 
-fn draw_capital(ui: &mut Ui, capital: &Arc<Capital>, is_selected: bool, is_hovered: bool) {
-    let coords = capital.coordinates;
-    let position = Pos2::new(coords.x, -coords.y);
+/// This map's view of the shared, projected world mesh.
+///
+/// The geometry is shared with every map at the same detail level and projection, each map only
+/// keeps its country colours and the triangles of the countries in view. The whole map is drawn as
+/// two shapes.
+#[derive(Debug, Default)]
+struct MapMesh {
+    geometry: Option<Arc<ProjectedWorldMesh>>,
+    country_colors: HashMap<String, Color32>,
+    border_color: Color32,
+    fill_indices: Vec<u32>,
+    outline_indices: Vec<u32>,
+    /// Countries in view in drawing order, with their ranges in the culled indices.
+    culled_countries: Vec<(String, Range<usize>, Range<usize>)>,
+    culled_rect: Option<Rect>,
+}
+
+impl MapMesh {
+    /// Switching the level or projection picks up the shared geometry for it.
+    fn set_geometry(&mut self, detail_level: usize, projection: Projection) {
+        let is_current = self.geometry.as_ref().is_some_and(|geometry| {
+            geometry.detail_level == detail_level && geometry.projection == projection
+        });
+        if !is_current {
+            self.geometry = Some(get_data().get_projected_world_mesh(detail_level, projection));
+            self.culled_rect = None;
+        }
+    }
+
+    fn update_colors(&mut self, country_colors: &[(&str, Color32)], border_color: Color32) {
+        for (country_code, color) in country_colors {
+            if self.country_colors.get(*country_code) != Some(color) {
+                self.country_colors.insert(country_code.to_string(), *color);
+            }
+        }
+        self.border_color = border_color;
+    }
+
+    /// Indexes the triangles of all countries overlapping the view, in drawing order.
+    fn update_culling(&mut self, view_rect: Rect) {
+        #[cfg(feature = "profiling")]
+        profiling::scope!("update_map_culling");
+
+        let Some(geometry) = &self.geometry else {
+            return;
+        };
+        if self.culled_rect == Some(view_rect) {
+            return;
+        }
+
+        let data = get_data();
+        let world_mesh = data.get_world_mesh(geometry.detail_level);
+        let geo_rect = geometry.projection.unproject_rect(view_rect);
+        let visible_countries = data.get_countries_in_rect(geo_rect, geometry.detail_level);
+        let is_globe = geometry.projection.is_globe();
+
+        self.fill_indices.clear();
+        self.outline_indices.clear();
+        self.culled_countries.clear();
 
+        for country_code in data.get_country_codes() {
+            if !visible_countries.contains(country_code.as_str()) {
+                continue;
+            }
+            let Some(ranges) = world_mesh.get_country_ranges(country_code) else {
+                continue;
+            };
+
+            let fill_start = self.fill_indices.len();
+            let outline_start = self.outline_indices.len();
+            let fill_triangles = &world_mesh.fill().indices[ranges.fill_indices.clone()];
+            let outline_triangles = &world_mesh.outline().indices[ranges.outline_indices.clone()];
+            if is_globe {
+                extend_front_triangles(&mut self.fill_indices, fill_triangles, &geometry.fill);
+                extend_front_triangles(
+                    &mut self.outline_indices,
+                    outline_triangles,
+                    &geometry.outline,
+                );
+            } else {
+                self.fill_indices.extend_from_slice(fill_triangles);
+                self.outline_indices.extend_from_slice(outline_triangles);
+            }
+            self.culled_countries.push((
+                country_code.clone(),
+                fill_start..self.fill_indices.len(),
+                outline_start..self.outline_indices.len(),
+            ));
+        }
+        self.culled_rect = Some(view_rect);
+    }
+
+    /// Builds the fill and outline of the countries in view from the shared geometry.
+    fn draw(&self, ui: &Ui) {
+        #[cfg(feature = "profiling")]
+        profiling::scope!("draw_map_mesh");
+
+        let Some(geometry) = &self.geometry else {
+            return;
+        };
+
+        let data = get_data();
+        let world_mesh = data.get_world_mesh(geometry.detail_level);
+        let mut fill = Mesh::default();
+        let mut outline = Mesh::default();
+
+        for (country_code, fill_indices, outline_indices) in &self.culled_countries {
+            let Some(ranges) = world_mesh.get_country_ranges(country_code) else {
+                continue;
+            };
+            let color = self
+                .country_colors
+                .get(country_code)
+                .copied()
+                .unwrap_or(Color32::PLACEHOLDER);
+
+            append_country(
+                &mut fill,
+                &geometry.fill,
+                ranges.fill_vertices.clone(),
+                &self.fill_indices[fill_indices.clone()],
+                color,
+            );
+            append_country(
+                &mut outline,
+                &geometry.outline,
+                ranges.outline_vertices.clone(),
+                &self.outline_indices[outline_indices.clone()],
+                self.border_color,
+            );
+        }
+
+        ui.painter().add(Shape::mesh(fill));
+        ui.painter().add(Shape::mesh(outline));
+    }
+}
+
+/// Adds a country's vertices in the given colour, with its triangles renumbered to match.
+fn append_country(
+    mesh: &mut Mesh,
+    positions: &[Pos2],
+    vertices: Range<usize>,
+    triangles: &[u32],
+    color: Color32,
+) {
+    if triangles.is_empty() {
+        return;
+    }
+
+    let base = mesh.vertices.len() as u32;
+    let start = vertices.start as u32;
+    mesh.vertices
+        .extend(positions[vertices].iter().map(|pos| Vertex {
+            pos: *pos,
+            uv: WHITE_UV,
+            color,
+        }));
+    mesh.indices
+        .extend(triangles.iter().map(|index| base + index - start));
+}
+
+/// Adds only the triangles on the visible side of the globe.
+fn extend_front_triangles(indices: &mut Vec<u32>, triangles: &[u32], positions: &[Pos2]) {
+    for triangle in triangles.chunks_exact(3) {
+        if triangle
+            .iter()
+            .all(|index| positions[*index as usize].x.is_finite())
+        {
+            indices.extend_from_slice(triangle);
+        }
+    }
+}
+
+fn draw_capital(
+    ui: &mut Ui,
+    theme: &MapTheme,
+    position: Pos2,
+    is_selected: bool,
+    is_hovered: bool,
+) {
     let color = if is_selected {
-        Color32::from_rgb(0, 0, 255)
+        theme.capital_selected
     } else if is_hovered {
-        Color32::from_rgb(0, 255, 0)
+        theme.capital_hovered
     } else {
-        Color32::from_rgb(255, 0, 0)
+        theme.capital
     };
 
     ui.painter().circle(
         position,
         0.025,
         color,
-        Stroke::new(0.01, Color32::from_rgb(0, 0, 0)),
+        Stroke::new(0.01, theme.capital_outline),
     );
 }
+
+/// Converts a length in screen pixels to map units at the current zoom level.
+pub fn pixels_to_map_units(ui: &Ui, pixels: f32) -> f32 {
+    ui.ctx()
+        .layer_transform_to_global(ui.layer_id())
+        .map(|transform| pixels / transform.scaling)
+        .unwrap_or(pixels)
+}
@@ -1,9 +1,35 @@
 use crate::app::AppState;
+use crate::utils::time::timestamp_ms;
 use directories::ProjectDirs;
-use log::error;
-use std::path::PathBuf;
+use log::{error, info};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
+pub mod autosave;
+pub mod bundle;
 pub mod persistent_object;
+pub mod profiles;
+
+const SAVE_FILE_NAME: &str = "save.json";
+/// How many saves from earlier runs are kept as rolling backups.
+const SAVE_BACKUPS: usize = 3;
+
+/// Version of the save format. Bump it and add a migration whenever a change would stop older
+/// saves from loading, e.g. renaming or retyping a persisted field.
+const SAVE_VERSION: u64 = 1;
+
+/// The migration at index `n` upgrades a version `n` document to version `n + 1`.
+const MIGRATIONS: [fn(Value) -> Result<Value, String>; SAVE_VERSION as usize] = [migrate_v0_to_v1];
+
+/// What's written to the save file, the state together with the version of its format.
+#[derive(Serialize)]
+struct SaveFile<'a> {
+    version: u64,
+    state: &'a AppState,
+}
 
 fn get_project_dirs() -> ProjectDirs {
     ProjectDirs::from("io.github", "zitronenjoghurt", "world-study").unwrap()
@@ -13,41 +39,452 @@ fn get_save_dir() -> PathBuf {
     get_project_dirs().data_dir().to_path_buf()
 }
 
-fn get_save_file_path() -> PathBuf {
-    get_save_dir().join("save.json")
+fn get_save_file_path(directory: &Path) -> PathBuf {
+    directory.join(SAVE_FILE_NAME)
+}
+
+/// Rolling backups from earlier runs, `save.1.json` being the most recent.
+fn get_backup_file_path(directory: &Path, index: usize) -> PathBuf {
+    directory.join(format!("save.{index}.json"))
 }
 
-pub fn persist_state(state: AppState) {
-    let directory = get_save_dir();
-    if !directory.exists() {
-        std::fs::create_dir_all(&directory).unwrap();
+/// Outcome of loading the save file.
+#[derive(Debug, Default)]
+pub struct RestoredState {
+    pub state: Option<AppState>,
+    /// What went wrong while loading, for telling the user.
+    pub notice: Option<String>,
+    /// The save is from a newer version of the app, it must not be written to.
+    pub read_only: bool,
+}
+
+/// Writes the save file atomically, a crash mid-save leaves the previous save in place.
+///
+/// With `rotate` the previous save is kept as a rolling backup first, done once per run so the
+/// backups stay saves from earlier runs.
+fn write_save(directory: &Path, data: &str, rotate: bool) -> bool {
+    if let Err(e) = std::fs::create_dir_all(directory) {
+        error!("Failed to create save directory: {}", e);
+        return false;
+    }
+
+    let temp_path = directory.join(format!("{SAVE_FILE_NAME}.tmp"));
+    if let Err(e) = write_synced(&temp_path, data) {
+        error!("Failed to write save file: {}", e);
+        return false;
+    }
+
+    if rotate {
+        rotate_backups(directory);
+    }
+    if let Err(e) = std::fs::rename(&temp_path, get_save_file_path(directory)) {
+        error!("Failed to replace save file: {}", e);
+        return false;
     }
+    true
+}
 
-    let save_path = get_save_file_path();
-    let Ok(data) = serde_json::to_string_pretty(&state) else {
-        error!("Failed to serialize app state to JSON");
+fn write_synced(path: &Path, data: &str) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(data.as_bytes())?;
+    file.sync_all()
+}
+
+/// Shifts the rolling backups by one, the current save becomes `save.1.json` and the oldest one
+/// is dropped.
+fn rotate_backups(directory: &Path) {
+    let save_path = get_save_file_path(directory);
+    if !save_path.exists() {
         return;
-    };
+    }
 
-    let write_result = std::fs::write(save_path, data);
-    if let Err(e) = write_result {
-        error!("Failed to write save file: {}", e);
+    for index in (1..SAVE_BACKUPS).rev() {
+        let backup_path = get_backup_file_path(directory, index);
+        if backup_path.exists()
+            && let Err(e) =
+                std::fs::rename(&backup_path, get_backup_file_path(directory, index + 1))
+        {
+            error!("Failed to rotate save backup: {}", e);
+        }
+    }
+
+    if let Err(e) = std::fs::copy(&save_path, get_backup_file_path(directory, 1)) {
+        error!("Failed to back up save file: {}", e);
     }
 }
 
-pub fn restore_state() -> Option<AppState> {
-    let save_path = get_save_file_path();
+/// Loads the save file in the directory, upgrading it from older versions if needed.
+///
+/// Saves that can't be loaded are kept next to the save file instead of being overwritten.
+/// A save from a newer version isn't broken, it's left as is and the profile opens read-only.
+/// A broken save is moved aside and recovered from, preferring whatever keeps the most recent
+/// quiz history: the readable parts of the save itself, then the rolling backups in full and
+/// finally the readable parts of the backups.
+pub fn restore_state(directory: &Path) -> RestoredState {
+    let save_path = get_save_file_path(directory);
     if !save_path.exists() {
-        return None;
+        return RestoredState::default();
     }
 
-    let Ok(data) = std::fs::read_to_string(save_path) else {
-        panic!("Failed to read save file");
+    let data = std::fs::read_to_string(&save_path).map_err(|e| e.to_string());
+    match data.as_deref().ok().and_then(save_version) {
+        Some(version) if version > SAVE_VERSION => {
+            error!(
+                "Save file version {version} is newer than the supported version {SAVE_VERSION}"
+            );
+            return RestoredState {
+                state: None,
+                notice: Some(format!(
+                    "This profile was saved by a newer version of World Study (save version \
+                     {version}, this one supports up to {SAVE_VERSION}). It's opened read-only, \
+                     nothing will be saved until World Study is updated."
+                )),
+                read_only: true,
+            };
+        }
+        Some(version) if version < SAVE_VERSION => {
+            backup_save_file(directory, &format!("v{version}"));
+        }
+        _ => {}
+    }
+
+    let error = match data.clone().and_then(|data| deserialize_save(&data)) {
+        Ok(state) => {
+            return RestoredState {
+                state: Some(state),
+                notice: None,
+                read_only: false,
+            };
+        }
+        Err(e) => e,
     };
+    error!("Failed to load save file: {}", error);
 
-    let Ok(state) = serde_json::from_str(&data) else {
-        panic!("Failed to deserialize save file");
+    let kept_at = match move_broken_save_file(directory) {
+        Some(path) => format!("The original was kept at {}.", path.display()),
+        None => "The original couldn't be kept.".to_string(),
     };
 
-    Some(state)
+    if let Ok(data) = &data
+        && let Some((state, reset)) = deserialize_partial_save(data)
+    {
+        return RestoredState {
+            state: Some(state),
+            notice: Some(format!(
+                "Parts of your save couldn't be loaded and were reset: {}. {kept_at}",
+                reset.join(", ")
+            )),
+            read_only: false,
+        };
+    }
+
+    let backups: Vec<String> = (1..=SAVE_BACKUPS)
+        .filter_map(|index| std::fs::read_to_string(get_backup_file_path(directory, index)).ok())
+        .collect();
+
+    if let Some(state) = backups.iter().find_map(|data| deserialize_save(data).ok()) {
+        return RestoredState {
+            state: Some(state),
+            notice: Some(format!(
+                "Your save couldn't be loaded, it was restored from a backup of an earlier run. \
+                 {kept_at}"
+            )),
+            read_only: false,
+        };
+    }
+
+    if let Some((state, reset)) = backups
+        .iter()
+        .find_map(|data| deserialize_partial_save(data))
+    {
+        return RestoredState {
+            state: Some(state),
+            notice: Some(format!(
+                "Your save couldn't be loaded, it was restored from a backup of an earlier run \
+                 with these parts reset: {}. {kept_at}",
+                reset.join(", ")
+            )),
+            read_only: false,
+        };
+    }
+
+    RestoredState {
+        state: None,
+        notice: Some(format!(
+            "Your save couldn't be loaded and no backup could be restored, starting fresh. \
+             {kept_at}"
+        )),
+        read_only: false,
+    }
+}
+
+/// Goes through a [`Value`], which sorts map keys, so the same state always gives the same text.
+/// Hash maps in the state would otherwise be written in a different order on every run.
+fn serialize_save(state: &AppState) -> serde_json::Result<String> {
+    let document = serde_json::to_value(SaveFile {
+        version: SAVE_VERSION,
+        state,
+    })?;
+    serde_json::to_string_pretty(&document)
+}
+
+fn deserialize_save(data: &str) -> Result<AppState, String> {
+    let document: Value = serde_json::from_str(data).map_err(|e| e.to_string())?;
+    let document = migrate(document)?;
+    serde_json::from_value(document["state"].clone()).map_err(|e| e.to_string())
+}
+
+/// Loads the parts of the state that are still readable and resets the rest to defaults.
+///
+/// Returns the state together with the fields that were reset, `None` if the quiz history is lost
+/// too since there'd be nothing worth keeping.
+fn deserialize_partial_save(data: &str) -> Option<(AppState, Vec<String>)> {
+    let document = migrate(serde_json::from_str(data).ok()?).ok()?;
+    let saved = document.get("state")?.as_object()?;
+
+    let mut state = serde_json::to_value(AppState::default()).ok()?;
+    let fields: Vec<String> = state.as_object()?.keys().cloned().collect();
+    let mut reset = Vec::new();
+
+    for field in fields {
+        let Some(value) = saved.get(&field) else {
+            continue;
+        };
+        let default = std::mem::replace(&mut state[&field], value.clone());
+        if serde_json::from_value::<AppState>(state.clone()).is_err() {
+            state[&field] = default;
+            reset.push(field);
+        }
+    }
+
+    if reset.iter().any(|field| field == "quiz_history") {
+        return None;
+    }
+    let state = serde_json::from_value(state).ok()?;
+    Some((state, reset))
+}
+
+/// Version of a save document, saves from before versioning count as version 0.
+fn document_version(document: &Value) -> u64 {
+    document
+        .get("version")
+        .and_then(Value::as_u64)
+        .unwrap_or_default()
+}
+
+fn save_version(data: &str) -> Option<u64> {
+    serde_json::from_str::<Value>(data)
+        .ok()
+        .map(|document| document_version(&document))
+}
+
+/// Upgrades a save document to the current version, one version at a time.
+fn migrate(mut document: Value) -> Result<Value, String> {
+    let version = document_version(&document);
+    if version > SAVE_VERSION {
+        return Err(format!(
+            "Save file version {version} is newer than the supported version {SAVE_VERSION}"
+        ));
+    }
+
+    for (from_version, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        document = migration(document)?;
+        info!("Migrated save file from version {from_version}");
+    }
+    Ok(document)
+}
+
+/// Version 0 saves are the bare state without a version.
+fn migrate_v0_to_v1(document: Value) -> Result<Value, String> {
+    if !document.is_object() {
+        return Err("Version 0 save file is not a JSON object".to_string());
+    }
+    Ok(json!({ "version": 1, "state": document }))
+}
+
+fn get_named_backup_path(directory: &Path, reason: &str) -> PathBuf {
+    directory.join(format!("save.{reason}.{}.json", timestamp_ms()))
+}
+
+/// Copies the save file next to itself, e.g. `save.v0.1760000000000.json`.
+fn backup_save_file(directory: &Path, reason: &str) {
+    let backup_path = get_named_backup_path(directory, reason);
+    match std::fs::copy(get_save_file_path(directory), &backup_path) {
+        Ok(_) => info!("Backed up save file to {}", backup_path.display()),
+        Err(e) => error!("Failed to back up save file: {}", e),
+    }
+}
+
+/// Moves a save that can't be loaded out of the way, so it's neither loaded again nor rotated
+/// into the backups.
+fn move_broken_save_file(directory: &Path) -> Option<PathBuf> {
+    let backup_path = get_named_backup_path(directory, "broken");
+    match std::fs::rename(get_save_file_path(directory), &backup_path) {
+        Ok(_) => {
+            info!("Moved broken save file to {}", backup_path.display());
+            Some(backup_path)
+        }
+        Err(e) => {
+            error!("Failed to move broken save file: {}", e);
+            None
+        }
+    }
+}
+
+/// A fresh directory for a test to write files into.
+#[cfg(test)]
+pub(crate) fn test_dir(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!(
+        "world-study-{name}-{}-{}",
+        std::process::id(),
+        timestamp_ms()
+    ));
+    std::fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::components::quiz::stats::QuizStatsTrait;
+
+    const SAVE_V0: &str = include_str!("persistence/fixtures/save_v0.json");
+    const SAVE_V1: &str = include_str!("persistence/fixtures/save_v1.json");
+
+    #[test]
+    fn loads_version_0_save() {
+        let state = deserialize_save(SAVE_V0).unwrap();
+        assert_eq!(state.quiz_history.len(), 2);
+        assert_eq!(
+            state.quiz_history[0].get_common().finished_at_ms,
+            1760000012345
+        );
+    }
+
+    #[test]
+    fn loads_version_1_save() {
+        let state = deserialize_save(SAVE_V1).unwrap();
+        assert_eq!(state.quiz_history.len(), 3);
+        let common = state.quiz_history[2].get_common();
+        assert_eq!(common.subject.as_deref(), Some("PARIS"));
+        assert_eq!(common.success, Some(false));
+    }
+
+    #[test]
+    fn migrates_every_fixture_to_current_version() {
+        for fixture in [SAVE_V0, SAVE_V1] {
+            let document = migrate(serde_json::from_str(fixture).unwrap()).unwrap();
+            assert_eq!(document_version(&document), SAVE_VERSION);
+        }
+    }
+
+    #[test]
+    fn round_trips_current_save() {
+        let state = deserialize_save(SAVE_V1).unwrap();
+        let data = serialize_save(&state).unwrap();
+        assert_eq!(save_version(&data), Some(SAVE_VERSION));
+
+        let reloaded = deserialize_save(&data).unwrap();
+        assert_eq!(reloaded.quiz_history.len(), state.quiz_history.len());
+    }
+
+    #[test]
+    fn rejects_newer_save() {
+        let data = json!({ "version": SAVE_VERSION + 1, "state": {} }).to_string();
+        assert!(deserialize_save(&data).is_err());
+    }
+
+    fn count_files_starting_with(directory: &Path, prefix: &str) -> usize {
+        std::fs::read_dir(directory)
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(prefix)
+            })
+            .count()
+    }
+
+    #[test]
+    fn partial_load_keeps_quiz_history() {
+        let mut document: Value = serde_json::from_str(SAVE_V1).unwrap();
+        document["state"]["explore_state"] = json!("not an explore state");
+
+        let (state, reset) = deserialize_partial_save(&document.to_string()).unwrap();
+        assert_eq!(state.quiz_history.len(), 3);
+        assert_eq!(reset, vec!["explore_state".to_string()]);
+    }
+
+    #[test]
+    fn partial_load_gives_up_without_quiz_history() {
+        let mut document: Value = serde_json::from_str(SAVE_V1).unwrap();
+        document["state"]["quiz_history"] = json!(42);
+        assert!(deserialize_partial_save(&document.to_string()).is_none());
+    }
+
+    #[test]
+    fn saving_keeps_rolling_backups() {
+        let directory = test_dir("rolling");
+        let data = serialize_save(&deserialize_save(SAVE_V1).unwrap()).unwrap();
+        for _ in 0..SAVE_BACKUPS + 2 {
+            assert!(write_save(&directory, &data, true));
+        }
+
+        assert!(get_save_file_path(&directory).exists());
+        assert_eq!(
+            count_files_starting_with(&directory, "save."),
+            SAVE_BACKUPS + 1
+        );
+        assert!(get_backup_file_path(&directory, SAVE_BACKUPS).exists());
+        assert!(!directory.join("save.json.tmp").exists());
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn recovers_broken_save_from_backup() {
+        let directory = test_dir("recover");
+        let data = serialize_save(&deserialize_save(SAVE_V1).unwrap()).unwrap();
+        write_save(&directory, &data, true);
+        write_save(&directory, &data, true);
+        std::fs::write(get_save_file_path(&directory), "{ truncated").unwrap();
+
+        let restored = restore_state(&directory);
+        assert_eq!(restored.state.unwrap().quiz_history.len(), 3);
+        assert!(restored.notice.is_some());
+        assert!(!get_save_file_path(&directory).exists());
+        assert_eq!(count_files_starting_with(&directory, "save.broken."), 1);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn opens_newer_save_read_only() {
+        let directory = test_dir("newer");
+        let data = json!({ "version": SAVE_VERSION + 1, "state": {} }).to_string();
+        std::fs::write(get_save_file_path(&directory), &data).unwrap();
+
+        let restored = restore_state(&directory);
+        assert!(restored.read_only);
+        assert!(restored.state.is_none());
+        assert!(restored.notice.is_some());
+        assert_eq!(
+            std::fs::read_to_string(get_save_file_path(&directory)).unwrap(),
+            data
+        );
+        assert_eq!(count_files_starting_with(&directory, "save."), 1);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn starts_fresh_without_readable_save() {
+        let directory = test_dir("fresh");
+        std::fs::write(get_save_file_path(&directory), "{ truncated").unwrap();
+
+        let restored = restore_state(&directory);
+        assert!(restored.state.is_none());
+        assert!(restored.notice.is_some());
+        std::fs::remove_dir_all(directory).unwrap();
+    }
 }
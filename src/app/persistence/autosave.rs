@@ -0,0 +1,136 @@
+use crate::app::persistence::{serialize_save, write_save};
+use crate::app::AppState;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// How often the state is saved in the background, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AutosaveInterval(pub u64);
+
+impl AutosaveInterval {
+    pub const DEFAULT: Self = Self(30);
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.0)
+    }
+}
+
+impl Default for AutosaveInterval {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Saves the app state on a background thread, so serializing a long quiz history never blocks a
+/// frame.
+///
+/// The thread is started with the first save and only ever writes the newest state it was given.
+/// States that serialize to what was last written are skipped.
+#[derive(Debug)]
+pub struct Autosaver {
+    directory: PathBuf,
+    sender: Option<Sender<AppState>>,
+    worker: Option<JoinHandle<()>>,
+    last_save: Option<Instant>,
+}
+
+impl Autosaver {
+    pub fn new(directory: PathBuf) -> Self {
+        Self {
+            directory,
+            sender: None,
+            worker: None,
+            last_save: None,
+        }
+    }
+
+    /// Whether the timer ran out since the last save, the first call only starts it.
+    pub fn is_due(&mut self, interval: Duration) -> bool {
+        let last_save = *self.last_save.get_or_insert_with(Instant::now);
+        last_save.elapsed() >= interval
+    }
+
+    pub fn save(&mut self, state: AppState) {
+        self.last_save = Some(Instant::now());
+
+        let sender = self.sender.get_or_insert_with(|| {
+            let (sender, receiver) = channel();
+            let directory = self.directory.clone();
+            self.worker = Some(std::thread::spawn(move || run_worker(directory, receiver)));
+            sender
+        });
+
+        if sender.send(state).is_err() {
+            error!("Autosave thread stopped, state was not saved");
+        }
+    }
+
+    /// Saves the state one last time and waits until everything is written.
+    pub fn finish(&mut self, state: AppState) {
+        self.save(state);
+        self.sender = None;
+        if let Some(worker) = self.worker.take()
+            && worker.join().is_err()
+        {
+            error!("Autosave thread panicked");
+        }
+    }
+}
+
+fn run_worker(directory: PathBuf, receiver: Receiver<AppState>) {
+    let mut last_written: Option<String> = None;
+
+    while let Ok(mut state) = receiver.recv() {
+        // Saves that queued up while writing are outdated, only the newest one matters.
+        while let Ok(newer) = receiver.try_recv() {
+            state = newer;
+        }
+
+        let Ok(data) = serialize_save(&state) else {
+            error!("Failed to serialize app state to JSON");
+            continue;
+        };
+        if last_written.as_ref() == Some(&data) {
+            continue;
+        }
+
+        if write_save(&directory, &data, last_written.is_none()) {
+            info!("Saved app state");
+            last_written = Some(data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::persistence::{deserialize_save, get_save_file_path, test_dir};
+
+    #[test]
+    fn skips_unchanged_state() {
+        let directory = test_dir("autosave");
+        let data = include_str!("fixtures/save_v1.json");
+        let (sender, receiver) = channel();
+        let worker = {
+            let directory = directory.clone();
+            std::thread::spawn(move || run_worker(directory, receiver))
+        };
+
+        sender.send(deserialize_save(data).unwrap()).unwrap();
+        while !get_save_file_path(&directory).exists() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        std::fs::remove_file(get_save_file_path(&directory)).unwrap();
+
+        sender.send(deserialize_save(data).unwrap()).unwrap();
+        drop(sender);
+        worker.join().unwrap();
+        assert!(!get_save_file_path(&directory).exists());
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,285 @@
+use crate::app::components::map_theme::MapThemePreset;
+use crate::app::components::quiz::scheduler::ReviewScheduler;
+use crate::app::components::quiz::stats::{QuizStats, QuizStatsTrait};
+use crate::app::components::quiz::QuizType;
+use crate::app::persistence::autosave::AutosaveInterval;
+use crate::app::persistence::get_save_dir;
+use crate::app::views::quiz_menu::QuizMenuStatePersist;
+use crate::app::AppState;
+use crate::utils::time::{day_index, format_day, timestamp_ms};
+use directories::UserDirs;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Tells progress bundles apart from other JSON files, like a save file picked by mistake.
+const BUNDLE_FORMAT: &str = "world-study-progress";
+/// Version of the bundle format. It embeds the same types as the save file, so a save migration
+/// that changes them needs a bundle version bump too.
+const BUNDLE_VERSION: u64 = 1;
+
+/// A profile's progress and settings in one portable file, for moving between computers.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProgressBundle {
+    format: String,
+    version: u64,
+    pub profile_name: String,
+    exported_at_ms: u128,
+    quiz_history: Vec<QuizStats>,
+    review_scheduler: ReviewScheduler,
+    map_theme: MapThemePreset,
+    autosave_interval: AutosaveInterval,
+    quiz_menu_state: QuizMenuStatePersist,
+}
+
+impl ProgressBundle {
+    /// Takes the portable parts of a saved state, view states like the explore map stay behind.
+    pub fn from_state(state: AppState, profile_name: &str) -> Self {
+        Self {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            profile_name: profile_name.to_string(),
+            exported_at_ms: timestamp_ms(),
+            quiz_history: state.quiz_history,
+            review_scheduler: state.review_scheduler,
+            map_theme: state.map_theme,
+            autosave_interval: state.autosave_interval,
+            quiz_menu_state: state.quiz_menu_state,
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::parse(&data)
+    }
+
+    fn parse(data: &str) -> Result<Self, String> {
+        let document: serde_json::Value = serde_json::from_str(data).map_err(|e| e.to_string())?;
+        if document.get("format").and_then(|format| format.as_str()) != Some(BUNDLE_FORMAT) {
+            return Err("Not a World Study progress file".to_string());
+        }
+
+        let version = document
+            .get("version")
+            .and_then(|version| version.as_u64())
+            .unwrap_or_default();
+        if version > BUNDLE_VERSION {
+            return Err(format!(
+                "Progress file version {version} is newer than the supported version \
+                 {BUNDLE_VERSION}"
+            ));
+        }
+        serde_json::from_value(document).map_err(|e| e.to_string())
+    }
+
+    /// Writes the bundle to the downloads folder and returns where it ended up.
+    pub fn export(&self) -> Result<PathBuf, String> {
+        let directory = UserDirs::new()
+            .and_then(|dirs| {
+                dirs.download_dir()
+                    .map(Path::to_path_buf)
+                    .or_else(|| Some(dirs.home_dir().to_path_buf()))
+            })
+            .unwrap_or_else(get_save_dir);
+        self.write(&directory)
+    }
+
+    /// Earlier exports are never overwritten, another export on the same day gets a number.
+    fn write(&self, directory: &Path) -> Result<PathBuf, String> {
+        let name: String = self
+            .profile_name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect();
+        let day = format_day(day_index(self.exported_at_ms));
+        let data = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+
+        let mut path = directory.join(format!("world-study-{name}-{day}.json"));
+        let mut number = 2;
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(data.as_bytes()).map_err(|e| e.to_string())?;
+                    return Ok(path);
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    path = directory.join(format!("world-study-{name}-{day}-{number}.json"));
+                    number += 1;
+                }
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+    }
+
+    /// Adds the bundle's quiz history to the state.
+    ///
+    /// History entries are the same quiz when they share type, subject and finish time, those
+    /// already in the state are skipped. Returns how many entries were added.
+    ///
+    /// The state keeps its own settings unless `import_settings` is set, a state without any
+    /// history yet takes over the bundle's settings too.
+    pub fn merge_into(self, state: &mut AppState, import_settings: bool) -> usize {
+        let history_was_empty = state.quiz_history.is_empty();
+        let mut known: HashSet<(QuizType, u128, Option<String>)> =
+            state.quiz_history.iter().map(history_key).collect();
+
+        let before = state.quiz_history.len();
+        for stats in self.quiz_history {
+            if known.insert(history_key(&stats)) {
+                state.quiz_history.push(stats);
+            }
+        }
+        let added = state.quiz_history.len() - before;
+
+        state
+            .quiz_history
+            .sort_by_key(|stats| stats.get_common().finished_at_ms);
+
+        // Two schedulers can't be combined, but they're a replay of the history so the merged
+        // history gives the combined one.
+        if history_was_empty {
+            state.review_scheduler = self.review_scheduler;
+        } else if added > 0 {
+            state.review_scheduler = ReviewScheduler::from_history(&state.quiz_history);
+        }
+
+        if import_settings || history_was_empty {
+            state.map_theme = self.map_theme;
+            state.autosave_interval = self.autosave_interval;
+            state.quiz_menu_state = self.quiz_menu_state;
+        }
+        added
+    }
+}
+
+fn history_key(stats: &QuizStats) -> (QuizType, u128, Option<String>) {
+    let common = stats.get_common();
+    (
+        stats.quiz_type(),
+        common.finished_at_ms,
+        common.subject.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::persistence::{deserialize_save, test_dir};
+
+    const SAVE_V1: &str = include_str!("fixtures/save_v1.json");
+    const DAY_MS: u128 = 24 * 60 * 60 * 1000;
+
+    #[test]
+    fn round_trips_bundle() {
+        let bundle = ProgressBundle::from_state(deserialize_save(SAVE_V1).unwrap(), "Alice");
+        let data = serde_json::to_string(&bundle).unwrap();
+
+        let parsed = ProgressBundle::parse(&data).unwrap();
+        assert_eq!(parsed.profile_name, "Alice");
+        assert_eq!(parsed.quiz_history.len(), 3);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(ProgressBundle::parse(SAVE_V1).is_err());
+        assert!(ProgressBundle::parse("not json").is_err());
+    }
+
+    #[test]
+    fn merges_without_duplicates() {
+        let mut state = deserialize_save(SAVE_V1).unwrap();
+        let mut other = deserialize_save(SAVE_V1).unwrap();
+        let mut extra = other.quiz_history[0].clone();
+        if let QuizStats::FlagNameCountry(stats) = &mut extra {
+            stats.common.finished_at_ms += 1;
+        }
+        other.quiz_history.push(extra);
+
+        let added = ProgressBundle::from_state(other, "Laptop").merge_into(&mut state, false);
+        assert_eq!(added, 1);
+        assert_eq!(state.quiz_history.len(), 4);
+
+        let bundle = ProgressBundle::from_state(deserialize_save(SAVE_V1).unwrap(), "Again");
+        assert_eq!(bundle.merge_into(&mut state, false), 0);
+        assert_eq!(state.quiz_history.len(), 4);
+    }
+
+    #[test]
+    fn rebuilds_scheduler_from_merged_history() {
+        let mut state = deserialize_save(SAVE_V1).unwrap();
+        let mut other = deserialize_save(SAVE_V1).unwrap();
+        let mut extra = other.quiz_history[0].clone();
+        if let QuizStats::FlagNameCountry(stats) = &mut extra {
+            stats.common.finished_at_ms += DAY_MS;
+        }
+        let reviewed_at_ms = extra.get_common().finished_at_ms;
+        other.quiz_history.push(extra);
+
+        ProgressBundle::from_state(other, "Laptop").merge_into(&mut state, false);
+        let memory = state
+            .review_scheduler
+            .get_memory(QuizType::FlagNameCountry, "DE")
+            .unwrap();
+        assert_eq!(memory.repetitions, 2);
+        assert_eq!(memory.last_review_ms, reviewed_at_ms);
+    }
+
+    #[test]
+    fn takes_scheduler_of_bundle_into_empty_history() {
+        let mut state = AppState::default();
+        let bundle = ProgressBundle::from_state(deserialize_save(SAVE_V1).unwrap(), "Laptop");
+
+        assert_eq!(bundle.merge_into(&mut state, false), 3);
+        let memory = state
+            .review_scheduler
+            .get_memory(QuizType::CapitalLocation, "PARIS")
+            .unwrap();
+        assert_eq!(memory.repetitions, 0);
+    }
+
+    fn bundle_with_settings() -> ProgressBundle {
+        let mut other = deserialize_save(SAVE_V1).unwrap();
+        other.map_theme = MapThemePreset::HighContrast;
+        other.autosave_interval = AutosaveInterval(90);
+        ProgressBundle::from_state(other, "Laptop")
+    }
+
+    #[test]
+    fn keeps_local_settings_unless_asked() {
+        let mut state = deserialize_save(SAVE_V1).unwrap();
+        bundle_with_settings().merge_into(&mut state, false);
+        assert_eq!(state.map_theme, MapThemePreset::Classic);
+        assert_eq!(state.autosave_interval, AutosaveInterval::DEFAULT);
+
+        bundle_with_settings().merge_into(&mut state, true);
+        assert_eq!(state.map_theme, MapThemePreset::HighContrast);
+        assert_eq!(state.autosave_interval, AutosaveInterval(90));
+    }
+
+    #[test]
+    fn takes_settings_into_empty_history() {
+        let mut state = AppState::default();
+        bundle_with_settings().merge_into(&mut state, false);
+        assert_eq!(state.map_theme, MapThemePreset::HighContrast);
+        assert_eq!(state.autosave_interval, AutosaveInterval(90));
+    }
+
+    #[test]
+    fn never_overwrites_earlier_export() {
+        let directory = test_dir("bundle");
+        let bundle = ProgressBundle::from_state(deserialize_save(SAVE_V1).unwrap(), "Alice");
+
+        let first = bundle.write(&directory).unwrap();
+        let second = bundle.write(&directory).unwrap();
+        let third = bundle.write(&directory).unwrap();
+        assert_ne!(first, second);
+        assert_ne!(second, third);
+        assert!(second.to_string_lossy().ends_with("-2.json"));
+        for path in [first, second, third] {
+            assert!(ProgressBundle::read(&path).is_ok());
+        }
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
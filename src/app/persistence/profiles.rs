@@ -0,0 +1,283 @@
+use crate::app::persistence::get_save_dir;
+use crate::utils::time::timestamp_ms;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const PROFILES_DIR_NAME: &str = "profiles";
+/// Lives in each profile's directory, so the profile's name stays with its saves.
+const PROFILE_FILE_NAME: &str = "profile.json";
+const LAST_USED_FILE_NAME: &str = "last_profile.json";
+const DEFAULT_PROFILE_NAME: &str = "Default";
+const MAX_NAME_LENGTH: usize = 40;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    /// Name of the profile's directory, it stays the same when the profile is renamed.
+    #[serde(skip)]
+    pub id: String,
+    pub name: String,
+}
+
+/// The people sharing this computer, each with their own save directory under `profiles/`.
+#[derive(Debug, Default)]
+pub struct Profiles {
+    directory: PathBuf,
+    profiles: Vec<Profile>,
+    last_used: Option<String>,
+}
+
+impl Profiles {
+    pub fn load() -> Self {
+        Self::load_from(get_save_dir())
+    }
+
+    /// Reads every profile directory, the first run with profiles moves an existing save into a
+    /// default profile.
+    fn load_from(directory: PathBuf) -> Self {
+        let mut profiles = Self {
+            profiles: read_profiles(&directory.join(PROFILES_DIR_NAME)),
+            last_used: std::fs::read_to_string(directory.join(LAST_USED_FILE_NAME))
+                .ok()
+                .and_then(|data| serde_json::from_str(&data).ok()),
+            directory,
+        };
+
+        if profiles.profiles.is_empty() {
+            match profiles.create(DEFAULT_PROFILE_NAME) {
+                Ok(profile) => profiles.adopt_saves_without_profile(&profile.id),
+                Err(e) => error!("Failed to create default profile: {}", e),
+            }
+        }
+        profiles
+    }
+
+    pub fn list(&self) -> &[Profile] {
+        &self.profiles
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.id == id)
+    }
+
+    pub fn last_used(&self) -> Option<&Profile> {
+        self.get(self.last_used.as_deref()?)
+    }
+
+    pub fn set_last_used(&mut self, id: &str) {
+        self.last_used = Some(id.to_string());
+        let path = self.directory.join(LAST_USED_FILE_NAME);
+        let result = serde_json::to_string(&self.last_used)
+            .map_err(|e| e.to_string())
+            .and_then(|data| std::fs::write(path, data).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            error!("Failed to remember last used profile: {}", e);
+        }
+    }
+
+    /// Directory the profile's save file and backups are kept in.
+    pub fn directory(&self, id: &str) -> PathBuf {
+        self.directory.join(PROFILES_DIR_NAME).join(id)
+    }
+
+    pub fn create(&mut self, name: &str) -> Result<Profile, String> {
+        let name = self.validate_name(name, None)?;
+        let id = self.new_id();
+        let profile = Profile { id, name };
+
+        std::fs::create_dir_all(self.directory(&profile.id)).map_err(|e| e.to_string())?;
+        self.write_profile(&profile)?;
+        self.profiles.push(profile.clone());
+        self.sort();
+        Ok(profile)
+    }
+
+    pub fn rename(&mut self, id: &str, name: &str) -> Result<(), String> {
+        let name = self.validate_name(name, Some(id))?;
+        let mut profile = self.get(id).ok_or("Profile doesn't exist")?.clone();
+        profile.name = name;
+
+        self.write_profile(&profile)?;
+        if let Some(existing) = self.profiles.iter_mut().find(|p| p.id == id) {
+            *existing = profile;
+        }
+        self.sort();
+        Ok(())
+    }
+
+    /// Creates a copy of the profile with all of its progress and settings.
+    pub fn duplicate(&mut self, id: &str) -> Result<Profile, String> {
+        let source = self.get(id).ok_or("Profile doesn't exist")?.clone();
+        let name = self.copy_name(&source.name);
+        let copy = self.create(&name)?;
+
+        let entries = std::fs::read_dir(self.directory(id)).map_err(|e| e.to_string())?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_file() || entry.file_name() == PROFILE_FILE_NAME {
+                continue;
+            }
+            std::fs::copy(&path, self.directory(&copy.id).join(entry.file_name()))
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(copy)
+    }
+
+    /// Deletes the profile together with all of its saves.
+    pub fn delete(&mut self, id: &str) -> Result<(), String> {
+        if self.get(id).is_none() {
+            return Err("Profile doesn't exist".to_string());
+        }
+
+        std::fs::remove_dir_all(self.directory(id)).map_err(|e| e.to_string())?;
+        self.profiles.retain(|profile| profile.id != id);
+        if self.last_used.as_deref() == Some(id) {
+            self.last_used = None;
+        }
+        Ok(())
+    }
+
+    /// Trimmed name if it's usable, names are unique regardless of case.
+    fn validate_name(&self, name: &str, renamed_id: Option<&str>) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Profile name can't be empty".to_string());
+        }
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(format!(
+                "Profile name can't be longer than {MAX_NAME_LENGTH} characters"
+            ));
+        }
+        if self.profiles.iter().any(|profile| {
+            Some(profile.id.as_str()) != renamed_id && profile.name.eq_ignore_ascii_case(name)
+        }) {
+            return Err(format!("A profile called \"{name}\" already exists"));
+        }
+        Ok(name.to_string())
+    }
+
+    fn copy_name(&self, name: &str) -> String {
+        let is_taken = |candidate: &str| {
+            self.profiles
+                .iter()
+                .any(|profile| profile.name.eq_ignore_ascii_case(candidate))
+        };
+
+        let mut candidate = format!("{name} (copy)");
+        let mut number = 2;
+        while is_taken(&candidate) {
+            candidate = format!("{name} (copy {number})");
+            number += 1;
+        }
+        candidate
+    }
+
+    fn new_id(&self) -> String {
+        let mut id = timestamp_ms();
+        while self.directory(&id.to_string()).exists() {
+            id += 1;
+        }
+        id.to_string()
+    }
+
+    fn write_profile(&self, profile: &Profile) -> Result<(), String> {
+        let data = serde_json::to_string_pretty(profile).map_err(|e| e.to_string())?;
+        std::fs::write(self.directory(&profile.id).join(PROFILE_FILE_NAME), data)
+            .map_err(|e| e.to_string())
+    }
+
+    fn sort(&mut self) {
+        self.profiles
+            .sort_by_key(|profile| profile.name.to_lowercase());
+    }
+
+    /// Moves saves from before profiles existed into the profile.
+    fn adopt_saves_without_profile(&mut self, id: &str) {
+        let Ok(entries) = std::fs::read_dir(&self.directory) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !(file_name.starts_with("save.") && file_name.ends_with(".json")) {
+                continue;
+            }
+            match std::fs::rename(entry.path(), self.directory(id).join(&file_name)) {
+                Ok(_) => info!("Moved {} into profile {}", file_name, id),
+                Err(e) => error!("Failed to move {} into profile: {}", file_name, e),
+            }
+        }
+    }
+}
+
+fn read_profiles(directory: &Path) -> Vec<Profile> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+
+    let mut profiles: Vec<Profile> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| {
+            let id = entry.file_name().to_string_lossy().to_string();
+            // A profile without a readable name is still listed, under its directory name.
+            let name = std::fs::read_to_string(entry.path().join(PROFILE_FILE_NAME))
+                .ok()
+                .and_then(|data| serde_json::from_str::<Profile>(&data).ok())
+                .map(|profile| profile.name)
+                .unwrap_or_else(|| id.clone());
+            Profile { id, name }
+        })
+        .collect();
+    profiles.sort_by_key(|profile| profile.name.to_lowercase());
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::persistence::test_dir;
+
+    #[test]
+    fn moves_existing_save_into_default_profile() {
+        let directory = test_dir("profiles-adopt");
+        std::fs::write(directory.join("save.json"), "{}").unwrap();
+        std::fs::write(directory.join("save.1.json"), "{}").unwrap();
+
+        let profiles = Profiles::load_from(directory.clone());
+        let [profile] = profiles.list() else {
+            panic!("Expected a single profile");
+        };
+        assert_eq!(profile.name, DEFAULT_PROFILE_NAME);
+        assert!(profiles.directory(&profile.id).join("save.json").exists());
+        assert!(profiles.directory(&profile.id).join("save.1.json").exists());
+        assert!(!directory.join("save.json").exists());
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn manages_profiles() {
+        let directory = test_dir("profiles-manage");
+        let mut profiles = Profiles::load_from(directory.clone());
+        let alice = profiles.create("Alice").unwrap();
+        std::fs::write(profiles.directory(&alice.id).join("save.json"), "{}").unwrap();
+
+        assert!(profiles.create(" alice ").is_err());
+        assert!(profiles.create("  ").is_err());
+
+        let copy = profiles.duplicate(&alice.id).unwrap();
+        assert_eq!(copy.name, "Alice (copy)");
+        assert!(profiles.directory(&copy.id).join("save.json").exists());
+
+        profiles.rename(&copy.id, "Bob").unwrap();
+        profiles.set_last_used(&copy.id);
+        profiles.delete(&alice.id).unwrap();
+        assert!(!profiles.directory(&alice.id).exists());
+
+        let reloaded = Profiles::load_from(directory.clone());
+        let names: Vec<&str> = reloaded.list().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Bob", DEFAULT_PROFILE_NAME]);
+        assert_eq!(reloaded.last_used().unwrap().name, "Bob");
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -15,6 +15,7 @@ impl ButtonSize {
         }
     }
 
+    #[allow(dead_code)]
     pub fn min_size(&self) -> Vec2 {
         Vec2::new(0.0, self.height())
     }
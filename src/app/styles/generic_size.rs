@@ -1,3 +1,4 @@
+#[allow(dead_code)]
 #[derive(Debug, Default, Copy, Clone)]
 pub enum GenericSize {
     S,
@@ -2,8 +2,10 @@ use serde::{Deserialize, Serialize};
 
 pub mod explore;
 pub mod main_menu;
+pub mod profile_menu;
 pub mod quiz_menu;
 pub mod quiz_run;
+pub mod statistics;
 pub mod study_menu;
 
 #[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
@@ -14,4 +16,6 @@ pub enum UIView {
     Explore,
     QuizMenu,
     QuizRun,
+    Statistics,
+    ProfileMenu,
 }
@@ -1,16 +1,24 @@
-use crate::app::components::world_map::{WorldMapState, WorldMapStatePersist};
+use crate::app::components::map_theme::MapThemePreset;
+use crate::app::components::quiz::QuizType;
+use crate::app::components::world_map::{MapMode, WorldMapState, WorldMapStatePersist};
 use crate::app::persistence::persistent_object::PersistentObject;
 use crate::app::views::explore::big_flag::render_big_flag_window;
 use crate::app::views::explore::debug::render_debug_window;
 use crate::app::views::explore::details::render_details_window;
+use crate::app::views::explore::mastery::{mastery_tints, render_mastery_window, MasteryCache};
 use crate::app::views::UIView;
 use crate::app::WorldStudyApp;
-use egui::Context;
+use crate::data::projection::Projection;
+use crate::get_data;
+use egui::{Color32, Context, Ui};
 use serde::{Deserialize, Serialize};
 
 mod big_flag;
 mod debug;
 mod details;
+mod mastery;
+
+const NEIGHBOR_COLOR: Color32 = Color32::from_rgb(236, 214, 160);
 
 #[derive(Debug)]
 pub struct ExploreState {
@@ -18,6 +26,10 @@ pub struct ExploreState {
     debug_window_enabled: bool,
     details_window_enabled: bool,
     big_flag_window_enabled: bool,
+    neighbors_highlighted: bool,
+    /// Colours the map by the accuracy of this quiz instead of the political colours.
+    mastery_quiz_type: Option<QuizType>,
+    mastery: MasteryCache,
 }
 
 impl Default for ExploreState {
@@ -27,16 +39,34 @@ impl Default for ExploreState {
             debug_window_enabled: false,
             details_window_enabled: true,
             big_flag_window_enabled: false,
+            neighbors_highlighted: true,
+            mastery_quiz_type: None,
+            mastery: MasteryCache::default(),
         }
     }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ExploreStatePersist {
     world_map: WorldMapStatePersist,
     debug_window_enabled: bool,
     details_window_enabled: bool,
     big_flag_window_enabled: bool,
+    /// Saves from before the setting existed keep the highlighting a new explore view has.
+    #[serde(default = "default_neighbors_highlighted")]
+    neighbors_highlighted: bool,
+    #[serde(default)]
+    mastery_quiz_type: Option<QuizType>,
+}
+
+impl Default for ExploreStatePersist {
+    fn default() -> Self {
+        ExploreState::default().save_state()
+    }
+}
+
+fn default_neighbors_highlighted() -> bool {
+    ExploreState::default().neighbors_highlighted
 }
 
 impl PersistentObject for ExploreState {
@@ -48,6 +78,8 @@ impl PersistentObject for ExploreState {
             debug_window_enabled: self.debug_window_enabled,
             details_window_enabled: self.details_window_enabled,
             big_flag_window_enabled: self.big_flag_window_enabled,
+            neighbors_highlighted: self.neighbors_highlighted,
+            mastery_quiz_type: self.mastery_quiz_type,
         }
     }
 
@@ -57,6 +89,9 @@ impl PersistentObject for ExploreState {
             debug_window_enabled: state.debug_window_enabled,
             details_window_enabled: state.details_window_enabled,
             big_flag_window_enabled: state.big_flag_window_enabled,
+            neighbors_highlighted: state.neighbors_highlighted,
+            mastery_quiz_type: state.mastery_quiz_type,
+            mastery: MasteryCache::default(),
         }
     }
 }
@@ -72,9 +107,45 @@ pub fn render(ctx: &Context, app: &mut WorldStudyApp) {
                 ui.checkbox(&mut app.explore_state.debug_window_enabled, "Debug");
                 ui.checkbox(&mut app.explore_state.details_window_enabled, "Details");
             });
+
+            ui.menu_button("Map  ", |ui| {
+                ui.checkbox(
+                    &mut app.explore_state.neighbors_highlighted,
+                    "Highlight neighbors",
+                );
+
+                ui.menu_button("Projection", |ui| {
+                    render_projection_menu(ui, &mut app.explore_state.world_map);
+                });
+                ui.menu_button("Theme", |ui| {
+                    for preset in MapThemePreset::ALL {
+                        ui.radio_value(&mut app.map_theme, preset, preset.label());
+                    }
+                });
+                ui.separator();
+
+                ui.radio_value(
+                    &mut app.explore_state.mastery_quiz_type,
+                    None,
+                    "Political colors",
+                );
+                ui.label("Mastery");
+                for quiz_type in QuizType::ALL {
+                    if quiz_type == QuizType::WorldCountries {
+                        continue;
+                    }
+                    ui.radio_value(
+                        &mut app.explore_state.mastery_quiz_type,
+                        Some(quiz_type),
+                        quiz_type.label(),
+                    );
+                }
+            });
         });
     });
 
+    update_neighbor_highlights(app);
+    update_map_mode(app);
     egui::CentralPanel::default().show(ctx, |ui| {
         app.explore_state.world_map.draw(ui);
     });
@@ -90,4 +161,88 @@ pub fn render(ctx: &Context, app: &mut WorldStudyApp) {
     if app.explore_state.big_flag_window_enabled {
         render_big_flag_window(ctx, app);
     }
+
+    render_mastery_window(ctx, app);
+}
+
+fn update_neighbor_highlights(app: &mut WorldStudyApp) {
+    let world_map = &mut app.explore_state.world_map;
+    world_map.clear_highlights();
+
+    if !app.explore_state.neighbors_highlighted {
+        return;
+    }
+
+    if let Some(selected_country) = world_map.selected_country.clone() {
+        for neighbor in get_data().get_neighbors(&selected_country) {
+            world_map.highlight_country(neighbor, NEIGHBOR_COLOR);
+        }
+    }
+}
+
+/// Tints are only recomputed when the quiz type or the history changed.
+fn update_map_mode(app: &mut WorldStudyApp) {
+    let explore_state = &mut app.explore_state;
+    let key = explore_state
+        .mastery_quiz_type
+        .map(|quiz_type| (quiz_type, app.quiz_history.len()));
+    if key == explore_state.mastery.tints_key {
+        return;
+    }
+
+    explore_state.mastery.tints_key = key;
+    explore_state.world_map.mode = match key {
+        Some((quiz_type, _)) => MapMode::Tinted(mastery_tints(&app.quiz_history, quiz_type)),
+        None => MapMode::Political,
+    };
+}
+
+fn render_projection_menu(ui: &mut Ui, world_map: &mut WorldMapState) {
+    let current = world_map.projection();
+    for projection in Projection::ALL {
+        if ui
+            .radio(current.same_kind(&projection), projection.label())
+            .clicked()
+            && !current.same_kind(&projection)
+        {
+            world_map.set_projection(projection);
+        }
+    }
+
+    if let Projection::Orthographic {
+        mut center_longitude,
+        mut center_latitude,
+    } = current
+    {
+        ui.separator();
+        let longitude_changed = ui
+            .add(egui::Slider::new(&mut center_longitude, -180.0..=180.0).text("Longitude"))
+            .changed();
+        let latitude_changed = ui
+            .add(egui::Slider::new(&mut center_latitude, -90.0..=90.0).text("Latitude"))
+            .changed();
+        if longitude_changed || latitude_changed {
+            world_map.set_projection(Projection::Orthographic {
+                center_longitude,
+                center_latitude,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlights_neighbors_in_saves_from_before_the_setting() {
+        let mut document = serde_json::to_value(ExploreStatePersist::default()).unwrap();
+        document
+            .as_object_mut()
+            .unwrap()
+            .remove("neighbors_highlighted");
+
+        let state: ExploreStatePersist = serde_json::from_value(document).unwrap();
+        assert!(state.neighbors_highlighted);
+    }
 }
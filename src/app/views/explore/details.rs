@@ -39,6 +39,20 @@ pub fn render_details_window(ctx: &Context, app: &mut WorldStudyApp) {
                         ui.strong("Official Name");
                         ui.label(&country.official_name);
                         ui.end_row();
+
+                        let neighbors = get_data().get_neighbors(selected_country).to_vec();
+                        if !neighbors.is_empty() {
+                            let neighbor_names: Vec<String> = get_data()
+                                .get_countries(
+                                    &neighbors.iter().map(|c| c.as_str()).collect::<Vec<_>>(),
+                                )
+                                .iter()
+                                .map(|neighbor| neighbor.common_name.clone())
+                                .collect();
+                            ui.strong("Neighbors");
+                            ui.label(neighbor_names.join(", "));
+                            ui.end_row();
+                        }
                     })
             });
     }
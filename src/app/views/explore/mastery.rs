@@ -0,0 +1,109 @@
+use crate::app::components::quiz::stats::{QuizStats, QuizStatsTrait};
+use crate::app::components::quiz::QuizType;
+use crate::app::WorldStudyApp;
+use crate::get_data;
+use crate::utils::time::{day_index, format_day};
+use egui::{Color32, Context, Id};
+use std::collections::HashMap;
+
+const WEAK_COLOR: Color32 = Color32::from_rgb(214, 69, 65);
+const STRONG_COLOR: Color32 = Color32::from_rgb(88, 180, 98);
+
+/// What the mastery map and window were last built for, so the history is only scanned again
+/// when the quiz type, the selected country or the history changes.
+#[derive(Debug, Default)]
+pub struct MasteryCache {
+    /// Quiz type and history length the map's tints were computed for.
+    pub tints_key: Option<(QuizType, usize)>,
+    attempts_key: Option<(QuizType, String, usize)>,
+    /// History indices of the selected country's attempts, newest first.
+    attempts: Vec<usize>,
+}
+
+/// Colours every asked country from red to green by the share of correct answers.
+pub fn mastery_tints(history: &[QuizStats], quiz_type: QuizType) -> HashMap<String, Color32> {
+    let mut tallies: HashMap<String, (u32, u32)> = HashMap::new();
+    for stats in history
+        .iter()
+        .filter(|stats| stats.quiz_type() == quiz_type)
+    {
+        let Some(success) = stats.get_common().success else {
+            continue;
+        };
+        let Some(country_code) = stats.subject_country() else {
+            continue;
+        };
+        let (correct, graded) = tallies.entry(country_code).or_default();
+        *correct += u32::from(success);
+        *graded += 1;
+    }
+
+    tallies
+        .into_iter()
+        .map(|(country_code, (correct, graded))| {
+            let accuracy = correct as f32 / graded as f32;
+            (
+                country_code,
+                WEAK_COLOR.lerp_to_gamma(STRONG_COLOR, accuracy),
+            )
+        })
+        .collect()
+}
+
+pub fn render_mastery_window(ctx: &Context, app: &mut WorldStudyApp) {
+    let Some(quiz_type) = app.explore_state.mastery_quiz_type else {
+        return;
+    };
+    let Some(selected_country) = &app.explore_state.world_map.selected_country else {
+        return;
+    };
+    let data = get_data();
+    let Some(country) = data.get_country(selected_country) else {
+        return;
+    };
+
+    let cache = &mut app.explore_state.mastery;
+    let key = Some((quiz_type, selected_country.clone(), app.quiz_history.len()));
+    if cache.attempts_key != key {
+        cache.attempts = (0..app.quiz_history.len())
+            .rev()
+            .filter(|index| {
+                let stats = &app.quiz_history[*index];
+                stats.quiz_type() == quiz_type
+                    && stats.subject_country().as_ref() == Some(selected_country)
+            })
+            .collect();
+        cache.attempts_key = key;
+    }
+    let attempts = cache
+        .attempts
+        .iter()
+        .filter_map(|index| app.quiz_history.get(*index));
+
+    egui::Window::new(format!("{}: {}", quiz_type.label(), country.common_name))
+        .id(Id::new("explore_mastery_window"))
+        .show(ctx, |ui| {
+            if cache.attempts.is_empty() {
+                ui.label("Never asked");
+                return;
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("explore_mastery_attempts")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for stats in attempts {
+                            let common = stats.get_common();
+                            ui.label(format_day(day_index(common.finished_at_ms)));
+                            ui.label(match common.success {
+                                Some(true) => "✔",
+                                Some(false) => "✘",
+                                None => "-",
+                            });
+                            ui.label(common.answers.join(", "));
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+}
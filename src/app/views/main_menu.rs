@@ -1,4 +1,6 @@
 use crate::app::components::custom_button::CustomButton;
+use crate::app::components::settings_slider::SettingsSlider;
+use crate::app::persistence::autosave::AutosaveInterval;
 use crate::app::styles::generic_size::GenericSize;
 use crate::app::views::UIView;
 use crate::app::WorldStudyApp;
@@ -15,8 +17,14 @@ pub fn render(ctx: &Context, app: &mut WorldStudyApp) {
         .fixed_size(Vec2::new(200.0, 100.0))
         .show(ctx, |ui| {
             ui.vertical_centered(|ui| {
+                if let Some(profile) = &app.profile {
+                    ui.label(format!("Profile: {}", profile.name));
+                }
                 menu_buttons(ui, app);
             });
+            ui.collapsing("Settings", |ui| {
+                settings(ui, app);
+            });
         });
 }
 
@@ -30,6 +38,27 @@ fn menu_buttons(ui: &mut Ui, app: &mut WorldStudyApp) {
     if menu_button(ui, "Explore").clicked() {
         app.switch_view(UIView::Explore);
     }
+    if menu_button(ui, "Statistics").clicked() {
+        app.switch_view(UIView::Statistics);
+    }
+    if menu_button(ui, "Profiles").clicked() {
+        app.switch_view(UIView::ProfileMenu);
+    }
+}
+
+fn settings(ui: &mut Ui, app: &mut WorldStudyApp) {
+    SettingsSlider::build()
+        .text("Autosave")
+        .tooltip("How often progress is saved, finished quizzes are saved right away.")
+        .suffix(" s")
+        .logarithmic(true)
+        .draw(
+            ui,
+            &mut app.autosave_interval.0,
+            AutosaveInterval::DEFAULT.0,
+            10..=600,
+            5.0,
+        );
 }
 
 fn menu_button(ui: &mut Ui, text: &str) -> Response {
@@ -0,0 +1,258 @@
+use crate::app::views::UIView;
+use crate::app::WorldStudyApp;
+use egui::{Color32, Context, Grid, RichText, Ui, Vec2};
+use std::path::PathBuf;
+
+#[derive(Debug, Default)]
+pub struct ProfileMenuState {
+    new_name: String,
+    /// Id of the profile being renamed and its new name.
+    renaming: Option<(String, String)>,
+    /// Id of the profile waiting for the deletion to be confirmed.
+    deleting: Option<String>,
+    error: Option<String>,
+    /// Outcome of the last export or import.
+    status: Option<String>,
+    import_path: String,
+    /// Whether an import replaces the profile's settings too, not just adds the quiz history.
+    import_settings: bool,
+}
+
+/// What was clicked, applied after drawing since the profile list is borrowed while drawing.
+enum ProfileAction {
+    Open(String),
+    StartRename(String),
+    Rename(String, String),
+    Duplicate(String),
+    StartDelete(String),
+    Delete(String),
+    Create(String),
+    Cancel,
+    Export,
+    Import(PathBuf),
+}
+
+pub fn render(ctx: &Context, app: &mut WorldStudyApp) {
+    egui::Window::new(RichText::new("Profiles").size(30.0))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .min_size(Vec2::new(300.0, 100.0))
+        .show(ctx, |ui| {
+            let mut action = draw_profiles(ui, app);
+            if app.profile.is_some() {
+                ui.separator();
+                action = draw_progress(ui, app).or(action);
+                action = dropped_file(ctx).or(action);
+            }
+            if let Some(action) = action {
+                apply_action(app, action);
+            }
+
+            if let Some(status) = &app.profile_menu_state.status {
+                ui.label(status);
+            }
+
+            if let Some(error) = &app.profile_menu_state.error {
+                ui.label(RichText::new(error).color(Color32::from_rgb(214, 69, 65)));
+            }
+
+            if app.profile.is_some() {
+                ui.separator();
+                if ui.button("Back").clicked() {
+                    app.switch_view(UIView::MainMenu);
+                }
+            }
+        });
+}
+
+fn draw_profiles(ui: &mut Ui, app: &mut WorldStudyApp) -> Option<ProfileAction> {
+    let mut action = None;
+    let state = &mut app.profile_menu_state;
+    let current_id = app.profile.as_ref().map(|profile| profile.id.as_str());
+    // Preselected on startup, so the usual profile is one click away.
+    let suggested_id = current_id.or(app.profiles.last_used().map(|profile| profile.id.as_str()));
+
+    Grid::new("profile_list")
+        .num_columns(2)
+        .spacing([12.0, 6.0])
+        .show(ui, |ui| {
+            for profile in app.profiles.list() {
+                let is_current = current_id == Some(profile.id.as_str());
+
+                match (&mut state.renaming, &state.deleting) {
+                    (Some((id, name)), _) if *id == profile.id => {
+                        ui.text_edit_singleline(name);
+                        ui.horizontal(|ui| {
+                            if ui.button("Save").clicked() {
+                                action = Some(ProfileAction::Rename(id.clone(), name.clone()));
+                            }
+                            if ui.button("Cancel").clicked() {
+                                action = Some(ProfileAction::Cancel);
+                            }
+                        });
+                    }
+                    (_, Some(id)) if *id == profile.id => {
+                        ui.label(format!("Delete \"{}\" and all its progress?", profile.name));
+                        ui.horizontal(|ui| {
+                            if ui.button("Delete").clicked() {
+                                action = Some(ProfileAction::Delete(id.clone()));
+                            }
+                            if ui.button("Cancel").clicked() {
+                                action = Some(ProfileAction::Cancel);
+                            }
+                        });
+                    }
+                    _ => {
+                        let mut name = RichText::new(&profile.name).size(18.0);
+                        if suggested_id == Some(profile.id.as_str()) {
+                            name = name.strong();
+                        }
+                        ui.label(name);
+
+                        ui.horizontal(|ui| {
+                            let open_text = if is_current { "Current" } else { "Open" };
+                            if ui
+                                .add_enabled(!is_current, egui::Button::new(open_text))
+                                .clicked()
+                            {
+                                action = Some(ProfileAction::Open(profile.id.clone()));
+                            }
+                            if ui.button("✏").on_hover_text("Rename").clicked() {
+                                action = Some(ProfileAction::StartRename(profile.id.clone()));
+                            }
+                            if ui.button("🗐").on_hover_text("Duplicate").clicked() {
+                                action = Some(ProfileAction::Duplicate(profile.id.clone()));
+                            }
+                            if ui
+                                .add_enabled(!is_current, egui::Button::new("🗑"))
+                                .on_hover_text("Delete")
+                                .on_disabled_hover_text("The open profile can't be deleted")
+                                .clicked()
+                            {
+                                action = Some(ProfileAction::StartDelete(profile.id.clone()));
+                            }
+                        });
+                    }
+                }
+                ui.end_row();
+            }
+        });
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut state.new_name).hint_text("New profile"));
+        if ui.button("Create").clicked() {
+            action = Some(ProfileAction::Create(state.new_name.clone()));
+        }
+    });
+
+    action
+}
+
+fn draw_progress(ui: &mut Ui, app: &mut WorldStudyApp) -> Option<ProfileAction> {
+    let mut action = None;
+    let state = &mut app.profile_menu_state;
+
+    if let Some(profile) = &app.profile {
+        ui.label(format!("Progress of {}", profile.name));
+    }
+    if ui
+        .button("Export")
+        .on_hover_text("Save quiz history and settings to the downloads folder")
+        .clicked()
+    {
+        action = Some(ProfileAction::Export);
+    }
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut state.import_path)
+                .hint_text("Progress file path, or drop it here"),
+        );
+        if ui.button("Import").clicked() {
+            action = Some(ProfileAction::Import(PathBuf::from(
+                state.import_path.trim(),
+            )));
+        }
+    });
+    ui.checkbox(&mut state.import_settings, "Also import settings")
+        .on_hover_text("Replace this profile's map theme, autosave and quiz settings too");
+
+    action
+}
+
+/// Files dropped onto the window are imported right away.
+fn dropped_file(ctx: &Context) -> Option<ProfileAction> {
+    ctx.input(|input| {
+        input
+            .raw
+            .dropped_files
+            .iter()
+            .find_map(|file| file.path.clone())
+    })
+    .map(ProfileAction::Import)
+}
+
+fn apply_action(app: &mut WorldStudyApp, action: ProfileAction) {
+    let result = match action {
+        ProfileAction::Open(id) => {
+            if let Some(profile) = app.profiles.get(&id).cloned() {
+                app.open_profile(profile);
+            }
+            Ok(())
+        }
+        ProfileAction::StartRename(id) => {
+            let name = app.profiles.get(&id).map(|profile| profile.name.clone());
+            app.profile_menu_state.renaming = name.map(|name| (id, name));
+            app.profile_menu_state.deleting = None;
+            Ok(())
+        }
+        ProfileAction::Rename(id, name) => app.profiles.rename(&id, &name).map(|_| {
+            if let Some(profile) = app.profile.as_mut().filter(|profile| profile.id == id) {
+                profile.name = name.trim().to_string();
+            }
+            app.profile_menu_state.renaming = None;
+        }),
+        ProfileAction::Duplicate(id) => {
+            // The copy should include progress that hasn't been autosaved yet.
+            if app.profile.as_ref().is_some_and(|profile| profile.id == id) {
+                app.flush_save();
+            }
+            app.profiles.duplicate(&id).map(|_| ())
+        }
+        ProfileAction::StartDelete(id) => {
+            app.profile_menu_state.deleting = Some(id);
+            app.profile_menu_state.renaming = None;
+            Ok(())
+        }
+        ProfileAction::Delete(id) => {
+            app.profile_menu_state.deleting = None;
+            app.profiles.delete(&id)
+        }
+        ProfileAction::Create(name) => app.profiles.create(&name).map(|_| {
+            app.profile_menu_state.new_name.clear();
+        }),
+        ProfileAction::Export => app.export_progress().map(|path| {
+            app.profile_menu_state.status = Some(format!("Exported to {}", path.display()));
+        }),
+        ProfileAction::Import(path) => {
+            let import_settings = app.profile_menu_state.import_settings;
+            // A profile without history takes the settings either way.
+            let takes_settings = import_settings || app.quiz_history.is_empty();
+            app.import_progress(&path, import_settings).map(|added| {
+                let settings = if takes_settings { "settings and " } else { "" };
+                app.profile_menu_state.status = Some(format!(
+                    "Imported {settings}{added} new quizzes from {}",
+                    path.display()
+                ));
+            })
+        }
+        ProfileAction::Cancel => {
+            app.profile_menu_state.renaming = None;
+            app.profile_menu_state.deleting = None;
+            Ok(())
+        }
+    };
+
+    app.profile_menu_state.error = result.err();
+}
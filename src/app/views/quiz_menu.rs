@@ -1,4 +1,11 @@
+use crate::app::components::quiz::settings::capital_location::CapitalLocationQuizSettings;
+use crate::app::components::quiz::settings::country_capital::CountryCapitalQuizSettings;
+use crate::app::components::quiz::settings::country_flag::CountryFlagQuizSettings;
+use crate::app::components::quiz::settings::country_neighbors::CountryNeighborsQuizSettings;
+use crate::app::components::quiz::settings::country_shape::CountryShapeQuizSettings;
 use crate::app::components::quiz::settings::flag_name_country::FlagNameCountryQuizSettings;
+use crate::app::components::quiz::settings::locate_country::LocateCountryQuizSettings;
+use crate::app::components::quiz::settings::world_countries::WorldCountriesQuizSettings;
 use crate::app::components::quiz::settings::QuizSettingsTrait;
 use crate::app::components::quiz::Quiz;
 use crate::app::persistence::persistent_object::PersistentObject;
@@ -11,6 +18,20 @@ use serde::{Deserialize, Serialize};
 pub struct QuizMenuState {
     flag_name_quiz_settings: FlagNameCountryQuizSettings,
     flag_name_quiz_enabled: bool,
+    locate_country_quiz_settings: LocateCountryQuizSettings,
+    locate_country_quiz_enabled: bool,
+    country_capital_quiz_settings: CountryCapitalQuizSettings,
+    country_capital_quiz_enabled: bool,
+    capital_location_quiz_settings: CapitalLocationQuizSettings,
+    capital_location_quiz_enabled: bool,
+    country_flag_quiz_settings: CountryFlagQuizSettings,
+    country_flag_quiz_enabled: bool,
+    country_shape_quiz_settings: CountryShapeQuizSettings,
+    country_shape_quiz_enabled: bool,
+    country_neighbors_quiz_settings: CountryNeighborsQuizSettings,
+    country_neighbors_quiz_enabled: bool,
+    world_countries_quiz_settings: WorldCountriesQuizSettings,
+    world_countries_quiz_enabled: bool,
 }
 
 impl QuizMenuState {
@@ -19,18 +40,74 @@ impl QuizMenuState {
         if self.flag_name_quiz_enabled {
             quizzes.push(self.flag_name_quiz_settings.create_quiz());
         }
+        if self.locate_country_quiz_enabled {
+            quizzes.push(self.locate_country_quiz_settings.create_quiz());
+        }
+        if self.country_capital_quiz_enabled {
+            quizzes.push(self.country_capital_quiz_settings.create_quiz());
+        }
+        if self.capital_location_quiz_enabled {
+            quizzes.push(self.capital_location_quiz_settings.create_quiz());
+        }
+        if self.country_flag_quiz_enabled {
+            quizzes.push(self.country_flag_quiz_settings.create_quiz());
+        }
+        if self.country_shape_quiz_enabled {
+            quizzes.push(self.country_shape_quiz_settings.create_quiz());
+        }
+        if self.country_neighbors_quiz_enabled {
+            quizzes.push(self.country_neighbors_quiz_settings.create_quiz());
+        }
+        if self.world_countries_quiz_enabled {
+            quizzes.push(self.world_countries_quiz_settings.create_quiz());
+        }
         quizzes
     }
 
     pub fn has_at_least_one_quiz_enabled(&self) -> bool {
         self.flag_name_quiz_enabled
+            || self.locate_country_quiz_enabled
+            || self.country_capital_quiz_enabled
+            || self.capital_location_quiz_enabled
+            || self.country_flag_quiz_enabled
+            || self.country_shape_quiz_enabled
+            || self.country_neighbors_quiz_enabled
+            || self.world_countries_quiz_enabled
     }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct QuizMenuStatePersist {
     flag_name_quiz_settings: FlagNameCountryQuizSettings,
     flag_name_quiz_enabled: bool,
+    #[serde(default)]
+    locate_country_quiz_settings: LocateCountryQuizSettings,
+    #[serde(default)]
+    locate_country_quiz_enabled: bool,
+    #[serde(default)]
+    country_capital_quiz_settings: CountryCapitalQuizSettings,
+    #[serde(default)]
+    country_capital_quiz_enabled: bool,
+    #[serde(default)]
+    capital_location_quiz_settings: CapitalLocationQuizSettings,
+    #[serde(default)]
+    capital_location_quiz_enabled: bool,
+    #[serde(default)]
+    country_flag_quiz_settings: CountryFlagQuizSettings,
+    #[serde(default)]
+    country_flag_quiz_enabled: bool,
+    #[serde(default)]
+    country_shape_quiz_settings: CountryShapeQuizSettings,
+    #[serde(default)]
+    country_shape_quiz_enabled: bool,
+    #[serde(default)]
+    country_neighbors_quiz_settings: CountryNeighborsQuizSettings,
+    #[serde(default)]
+    country_neighbors_quiz_enabled: bool,
+    #[serde(default)]
+    world_countries_quiz_settings: WorldCountriesQuizSettings,
+    #[serde(default)]
+    world_countries_quiz_enabled: bool,
 }
 
 impl PersistentObject for QuizMenuState {
@@ -40,6 +117,20 @@ impl PersistentObject for QuizMenuState {
         QuizMenuStatePersist {
             flag_name_quiz_settings: self.flag_name_quiz_settings.clone(),
             flag_name_quiz_enabled: self.flag_name_quiz_enabled,
+            locate_country_quiz_settings: self.locate_country_quiz_settings.clone(),
+            locate_country_quiz_enabled: self.locate_country_quiz_enabled,
+            country_capital_quiz_settings: self.country_capital_quiz_settings.clone(),
+            country_capital_quiz_enabled: self.country_capital_quiz_enabled,
+            capital_location_quiz_settings: self.capital_location_quiz_settings.clone(),
+            capital_location_quiz_enabled: self.capital_location_quiz_enabled,
+            country_flag_quiz_settings: self.country_flag_quiz_settings.clone(),
+            country_flag_quiz_enabled: self.country_flag_quiz_enabled,
+            country_shape_quiz_settings: self.country_shape_quiz_settings.clone(),
+            country_shape_quiz_enabled: self.country_shape_quiz_enabled,
+            country_neighbors_quiz_settings: self.country_neighbors_quiz_settings.clone(),
+            country_neighbors_quiz_enabled: self.country_neighbors_quiz_enabled,
+            world_countries_quiz_settings: self.world_countries_quiz_settings.clone(),
+            world_countries_quiz_enabled: self.world_countries_quiz_enabled,
         }
     }
 
@@ -47,6 +138,20 @@ impl PersistentObject for QuizMenuState {
         Self {
             flag_name_quiz_settings: state.flag_name_quiz_settings,
             flag_name_quiz_enabled: state.flag_name_quiz_enabled,
+            locate_country_quiz_settings: state.locate_country_quiz_settings,
+            locate_country_quiz_enabled: state.locate_country_quiz_enabled,
+            country_capital_quiz_settings: state.country_capital_quiz_settings,
+            country_capital_quiz_enabled: state.country_capital_quiz_enabled,
+            capital_location_quiz_settings: state.capital_location_quiz_settings,
+            capital_location_quiz_enabled: state.capital_location_quiz_enabled,
+            country_flag_quiz_settings: state.country_flag_quiz_settings,
+            country_flag_quiz_enabled: state.country_flag_quiz_enabled,
+            country_shape_quiz_settings: state.country_shape_quiz_settings,
+            country_shape_quiz_enabled: state.country_shape_quiz_enabled,
+            country_neighbors_quiz_settings: state.country_neighbors_quiz_settings,
+            country_neighbors_quiz_enabled: state.country_neighbors_quiz_enabled,
+            world_countries_quiz_settings: state.world_countries_quiz_settings,
+            world_countries_quiz_enabled: state.world_countries_quiz_enabled,
         }
     }
 }
@@ -62,6 +167,34 @@ pub fn render(ctx: &Context, app: &mut WorldStudyApp) {
                     &mut app.quiz_menu_state.flag_name_quiz_enabled,
                     "Flag ➡ Country",
                 );
+                ui.checkbox(
+                    &mut app.quiz_menu_state.locate_country_quiz_enabled,
+                    "Country ➡ Map",
+                );
+                ui.checkbox(
+                    &mut app.quiz_menu_state.country_capital_quiz_enabled,
+                    "Country ➡ Capital",
+                );
+                ui.checkbox(
+                    &mut app.quiz_menu_state.capital_location_quiz_enabled,
+                    "Capital ➡ Map",
+                );
+                ui.checkbox(
+                    &mut app.quiz_menu_state.country_flag_quiz_enabled,
+                    "Country ➡ Flag",
+                );
+                ui.checkbox(
+                    &mut app.quiz_menu_state.country_shape_quiz_enabled,
+                    "Shape ➡ Country",
+                );
+                ui.checkbox(
+                    &mut app.quiz_menu_state.country_neighbors_quiz_enabled,
+                    "Country ➡ Neighbors",
+                );
+                ui.checkbox(
+                    &mut app.quiz_menu_state.world_countries_quiz_enabled,
+                    "Name all countries",
+                );
             });
         });
     });
@@ -77,7 +210,8 @@ pub fn render(ctx: &Context, app: &mut WorldStudyApp) {
 
                 if start_button.clicked() {
                     let quizzes = app.quiz_menu_state.build_quizzes();
-                    app.quiz_run_state.load_quizzes(quizzes);
+                    app.quiz_run_state
+                        .load_quizzes(quizzes, &app.review_scheduler);
                     app.switch_view(UIView::QuizRun);
                 }
             });
@@ -91,6 +225,66 @@ pub fn render(ctx: &Context, app: &mut WorldStudyApp) {
             });
     }
 
+    if app.quiz_menu_state.locate_country_quiz_enabled {
+        egui::Window::new("Country ➡ Map")
+            .open(&mut app.quiz_menu_state.locate_country_quiz_enabled)
+            .show(ctx, |ui| {
+                app.quiz_menu_state.locate_country_quiz_settings.render(ui);
+            });
+    }
+
+    if app.quiz_menu_state.country_capital_quiz_enabled {
+        egui::Window::new("Country ➡ Capital")
+            .open(&mut app.quiz_menu_state.country_capital_quiz_enabled)
+            .show(ctx, |ui| {
+                app.quiz_menu_state.country_capital_quiz_settings.render(ui);
+            });
+    }
+
+    if app.quiz_menu_state.capital_location_quiz_enabled {
+        egui::Window::new("Capital ➡ Map")
+            .open(&mut app.quiz_menu_state.capital_location_quiz_enabled)
+            .show(ctx, |ui| {
+                app.quiz_menu_state
+                    .capital_location_quiz_settings
+                    .render(ui);
+            });
+    }
+
+    if app.quiz_menu_state.country_flag_quiz_enabled {
+        egui::Window::new("Country ➡ Flag")
+            .open(&mut app.quiz_menu_state.country_flag_quiz_enabled)
+            .show(ctx, |ui| {
+                app.quiz_menu_state.country_flag_quiz_settings.render(ui);
+            });
+    }
+
+    if app.quiz_menu_state.country_shape_quiz_enabled {
+        egui::Window::new("Shape ➡ Country")
+            .open(&mut app.quiz_menu_state.country_shape_quiz_enabled)
+            .show(ctx, |ui| {
+                app.quiz_menu_state.country_shape_quiz_settings.render(ui);
+            });
+    }
+
+    if app.quiz_menu_state.country_neighbors_quiz_enabled {
+        egui::Window::new("Country ➡ Neighbors")
+            .open(&mut app.quiz_menu_state.country_neighbors_quiz_enabled)
+            .show(ctx, |ui| {
+                app.quiz_menu_state
+                    .country_neighbors_quiz_settings
+                    .render(ui);
+            });
+    }
+
+    if app.quiz_menu_state.world_countries_quiz_enabled {
+        egui::Window::new("Name all countries")
+            .open(&mut app.quiz_menu_state.world_countries_quiz_enabled)
+            .show(ctx, |ui| {
+                app.quiz_menu_state.world_countries_quiz_settings.render(ui);
+            });
+    }
+
     //egui::CentralPanel::default().show(ctx, |ui| match app.quiz_menu_state.quiz.render(ui) {
     //    None => {}
     //    _ => app.quiz_menu_state.quiz.start(),
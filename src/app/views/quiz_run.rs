@@ -1,3 +1,4 @@
+use crate::app::components::quiz::scheduler::ReviewScheduler;
 use crate::app::components::quiz::{Quiz, QuizState, QuizTrait};
 use crate::app::persistence::persistent_object::PersistentObject;
 use crate::app::views::UIView;
@@ -14,24 +15,24 @@ pub struct QuizRunState {
 }
 
 impl QuizRunState {
-    pub fn load_quizzes(&mut self, quizzes: Vec<Quiz>) {
+    pub fn load_quizzes(&mut self, quizzes: Vec<Quiz>, scheduler: &ReviewScheduler) {
         self.quizzes = quizzes;
-        self.randomize_quiz();
+        self.randomize_quiz(scheduler);
     }
 
-    pub fn randomize_quiz(&mut self) {
+    pub fn randomize_quiz(&mut self, scheduler: &ReviewScheduler) {
         let mut rng = rand::rng();
         self.active_quiz = rng.random_range(0..self.quizzes.len());
-        self.restart_active_quiz();
+        self.restart_active_quiz(scheduler);
     }
 
     pub fn get_active_quiz(&mut self) -> &mut Quiz {
         &mut self.quizzes[self.active_quiz]
     }
 
-    pub fn restart_active_quiz(&mut self) {
+    pub fn restart_active_quiz(&mut self, scheduler: &ReviewScheduler) {
         self.stats_collected = false;
-        self.get_active_quiz().start()
+        self.get_active_quiz().start(scheduler)
     }
 }
 
@@ -72,18 +73,23 @@ pub fn render(ctx: &Context, app: &mut WorldStudyApp) {
     });
 
     egui::CentralPanel::default().show(ctx, |ui| {
-        if app.quiz_run_state.get_active_quiz().render(ui).is_some() {
-            if !app.quiz_run_state.stats_collected {
-                if let Some(stats) = app.quiz_run_state.get_active_quiz().collect_stats() {
-                    app.log_quiz_stats(stats);
-                    app.quiz_run_state.stats_collected = true;
-                }
+        let active_quiz = app.quiz_run_state.get_active_quiz();
+        if !active_quiz.has_started() {
+            active_quiz.start(&app.review_scheduler);
+        }
+
+        if active_quiz.render(ui).is_some() {
+            if !app.quiz_run_state.stats_collected
+                && let Some(stats) = app.quiz_run_state.get_active_quiz().collect_stats()
+            {
+                app.log_quiz_stats(stats);
+                app.quiz_run_state.stats_collected = true;
             }
 
             ui.add_space(5.0);
             let next_button = ui.vertical_centered(|ui| ui.button("Next"));
             if next_button.inner.clicked() || ui.input(|input| input.key_pressed(Key::Space)) {
-                app.quiz_run_state.randomize_quiz();
+                app.quiz_run_state.randomize_quiz(&app.review_scheduler);
             }
         }
     });
@@ -0,0 +1,264 @@
+use crate::app::components::daily_chart::DailyChart;
+use crate::app::components::quiz::stats::{QuizStats, QuizStatsTrait};
+use crate::app::components::quiz::QuizType;
+use crate::app::views::statistics::summary::{current_streak, StatisticsSummary, Tally};
+use crate::app::views::UIView;
+use crate::app::WorldStudyApp;
+use crate::get_data;
+use crate::utils::time::{day_index, timestamp_ms};
+use egui::{Context, RichText, Ui};
+
+mod summary;
+
+const LISTED_COUNTRIES: usize = 5;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DateRange {
+    Week,
+    #[default]
+    Month,
+    Quarter,
+    Year,
+    AllTime,
+}
+
+impl DateRange {
+    pub const ALL: [Self; 5] = [
+        Self::Week,
+        Self::Month,
+        Self::Quarter,
+        Self::Year,
+        Self::AllTime,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Week => "Last 7 days",
+            Self::Month => "Last 30 days",
+            Self::Quarter => "Last 90 days",
+            Self::Year => "Last 365 days",
+            Self::AllTime => "All time",
+        }
+    }
+
+    fn days(&self) -> Option<i64> {
+        match self {
+            Self::Week => Some(7),
+            Self::Month => Some(30),
+            Self::Quarter => Some(90),
+            Self::Year => Some(365),
+            Self::AllTime => None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct StatisticsState {
+    date_range: DateRange,
+    /// Aggregating the whole history is too slow for every frame, it's redone only when the date
+    /// range, the history or the day changes.
+    cached: Option<CachedStatistics>,
+}
+
+#[derive(Debug)]
+struct CachedStatistics {
+    /// Date range, history length and day the statistics were built for.
+    key: (DateRange, usize, i64),
+    first_day: i64,
+    summary: StatisticsSummary,
+    streak: u32,
+}
+
+impl CachedStatistics {
+    fn build(key: (DateRange, usize, i64), history: &[QuizStats]) -> Self {
+        let (date_range, _, today) = key;
+        let first_day = match date_range.days() {
+            Some(days) => today - days + 1,
+            None => history
+                .iter()
+                .map(|stats| day_index(stats.get_common().finished_at_ms))
+                .min()
+                .unwrap_or(today),
+        };
+
+        Self {
+            key,
+            first_day,
+            summary: StatisticsSummary::build(history, first_day),
+            streak: current_streak(history, today),
+        }
+    }
+}
+
+pub fn render(ctx: &Context, app: &mut WorldStudyApp) {
+    egui::TopBottomPanel::top("statistics_top_panel").show(ctx, |ui| {
+        egui::menu::bar(ui, |ui| {
+            if ui.button(" 🏠 ").clicked() {
+                app.switch_view(UIView::MainMenu);
+            }
+
+            egui::ComboBox::from_id_salt("statistics_date_range")
+                .selected_text(app.statistics_state.date_range.label())
+                .show_ui(ui, |ui| {
+                    for date_range in DateRange::ALL {
+                        ui.selectable_value(
+                            &mut app.statistics_state.date_range,
+                            date_range,
+                            date_range.label(),
+                        );
+                    }
+                });
+        });
+    });
+
+    let today = day_index(timestamp_ms());
+    let key = (
+        app.statistics_state.date_range,
+        app.quiz_history.len(),
+        today,
+    );
+    let cached = match &mut app.statistics_state.cached {
+        Some(cached) if cached.key == key => cached,
+        cached => cached.insert(CachedStatistics::build(key, &app.quiz_history)),
+    };
+    let (first_day, streak, summary) = (cached.first_day, cached.streak, &cached.summary);
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            render_overview(ui, &summary.overall, streak);
+            ui.separator();
+
+            ui.heading("Accuracy");
+            let accuracy_per_day: Vec<(i64, f32)> = summary
+                .per_day
+                .iter()
+                .filter_map(|(day, tally)| Some((*day, tally.accuracy()?)))
+                .collect();
+            DailyChart::new(first_day, today)
+                .max_value(100.0)
+                .unit("%")
+                .draw(ui, &accuracy_per_day);
+
+            ui.heading("Average time");
+            let time_per_day: Vec<(i64, f32)> = summary
+                .per_day
+                .iter()
+                .filter_map(|(day, tally)| Some((*day, tally.average_seconds()?)))
+                .collect();
+            DailyChart::new(first_day, today)
+                .unit("s")
+                .draw(ui, &time_per_day);
+            ui.separator();
+
+            ui.columns(2, |columns| {
+                columns[0].heading("Per quiz");
+                let quiz_rows = QuizType::ALL.iter().filter_map(|quiz_type| {
+                    let tally = summary.per_quiz_type.get(quiz_type)?;
+                    Some((quiz_type.label().to_string(), *tally))
+                });
+                render_tally_grid(&mut columns[0], "statistics_quiz_types", quiz_rows);
+
+                columns[1].heading("Per region");
+                let region_rows = summary
+                    .per_region
+                    .iter()
+                    .map(|(region, tally)| (region.clone(), *tally));
+                render_tally_grid(&mut columns[1], "statistics_regions", region_rows);
+            });
+            ui.separator();
+
+            render_countries(ui, summary);
+        });
+    });
+}
+
+fn render_overview(ui: &mut Ui, overall: &Tally, streak: u32) {
+    ui.horizontal(|ui| {
+        overview_value(ui, "Quizzes", overall.quizzes.to_string());
+        overview_value(ui, "Accuracy", format_accuracy(overall));
+        overview_value(ui, "Average time", format_seconds(overall));
+        overview_value(
+            ui,
+            "Streak",
+            format!("{streak} day{}", if streak == 1 { "" } else { "s" }),
+        );
+    });
+}
+
+fn overview_value(ui: &mut Ui, label: &str, value: String) {
+    ui.vertical(|ui| {
+        ui.label(label);
+        ui.label(RichText::new(value).size(24.0).strong());
+    });
+    ui.add_space(30.0);
+}
+
+fn render_tally_grid(ui: &mut Ui, id: &str, rows: impl Iterator<Item = (String, Tally)>) {
+    egui::Grid::new(id).striped(true).show(ui, |ui| {
+        ui.strong("");
+        ui.strong("Quizzes");
+        ui.strong("Accuracy");
+        ui.strong("Time");
+        ui.end_row();
+
+        for (name, tally) in rows {
+            ui.label(name);
+            ui.label(tally.quizzes.to_string());
+            ui.label(format_accuracy(&tally));
+            ui.label(format_seconds(&tally));
+            ui.end_row();
+        }
+    });
+}
+
+fn render_countries(ui: &mut Ui, summary: &StatisticsSummary) {
+    let countries = summary.countries_by_accuracy();
+    let best: Vec<&(String, Tally)> = countries.iter().take(LISTED_COUNTRIES).collect();
+    let worst: Vec<&(String, Tally)> = countries
+        .iter()
+        .rev()
+        .filter(|(code, _)| !best.iter().any(|(best_code, _)| best_code == code))
+        .take(LISTED_COUNTRIES)
+        .collect();
+
+    ui.columns(2, |columns| {
+        columns[0].heading("Best countries");
+        render_country_list(&mut columns[0], &best);
+        columns[1].heading("Weakest countries");
+        render_country_list(&mut columns[1], &worst);
+    });
+}
+
+fn render_country_list(ui: &mut Ui, countries: &[&(String, Tally)]) {
+    if countries.is_empty() {
+        ui.label("No answers yet");
+        return;
+    }
+
+    for (country_code, tally) in countries {
+        let name = get_data()
+            .get_country(country_code)
+            .map(|country| country.common_name.clone())
+            .unwrap_or_else(|| country_code.clone());
+        ui.label(format!(
+            "{name}: {} of {} ({})",
+            tally.correct,
+            tally.graded,
+            format_accuracy(tally)
+        ));
+    }
+}
+
+fn format_accuracy(tally: &Tally) -> String {
+    tally
+        .accuracy()
+        .map(|accuracy| format!("{accuracy:.0}%"))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn format_seconds(tally: &Tally) -> String {
+    tally
+        .average_seconds()
+        .map(|seconds| format!("{seconds:.1}s"))
+        .unwrap_or_else(|| "-".to_string())
+}
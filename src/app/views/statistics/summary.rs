@@ -0,0 +1,239 @@
+use crate::app::components::quiz::stats::{QuizStats, QuizStatsCommon, QuizStatsTrait};
+use crate::app::components::quiz::QuizType;
+use crate::get_data;
+use crate::utils::time::day_index;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Counts and timings of a group of finished quizzes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Tally {
+    pub quizzes: usize,
+    /// Quizzes with a known outcome, entries from older saves don't have one.
+    pub graded: usize,
+    pub correct: usize,
+    pub duration_ms: u128,
+}
+
+impl Tally {
+    fn add(&mut self, common: &QuizStatsCommon) {
+        self.quizzes += 1;
+        self.duration_ms += common.finished_at_ms.saturating_sub(common.started_at_ms);
+        if let Some(success) = common.success {
+            self.graded += 1;
+            self.correct += usize::from(success);
+        }
+    }
+
+    /// Share of correctly answered quizzes in percent.
+    pub fn accuracy(&self) -> Option<f32> {
+        (self.graded > 0).then(|| self.correct as f32 / self.graded as f32 * 100.0)
+    }
+
+    pub fn average_seconds(&self) -> Option<f32> {
+        (self.quizzes > 0).then(|| self.duration_ms as f32 / self.quizzes as f32 / 1000.0)
+    }
+}
+
+/// The quiz history aggregated over a range of days.
+#[derive(Debug, Default)]
+pub struct StatisticsSummary {
+    pub overall: Tally,
+    pub per_day: BTreeMap<i64, Tally>,
+    pub per_quiz_type: HashMap<QuizType, Tally>,
+    pub per_region: BTreeMap<String, Tally>,
+    pub per_country: HashMap<String, Tally>,
+}
+
+impl StatisticsSummary {
+    /// Aggregates all quizzes finished on or after `first_day`.
+    pub fn build(history: &[QuizStats], first_day: i64) -> Self {
+        let mut summary = Self::default();
+
+        for stats in history {
+            let common = stats.get_common();
+            let day = day_index(common.finished_at_ms);
+            if day < first_day {
+                continue;
+            }
+
+            summary.overall.add(common);
+            summary.per_day.entry(day).or_default().add(common);
+            summary
+                .per_quiz_type
+                .entry(stats.quiz_type())
+                .or_default()
+                .add(common);
+
+            let Some(country_code) = stats.subject_country() else {
+                continue;
+            };
+            if let Some(country) = get_data().get_country(&country_code) {
+                summary
+                    .per_region
+                    .entry(country.region.clone())
+                    .or_default()
+                    .add(common);
+            }
+            summary
+                .per_country
+                .entry(country_code)
+                .or_default()
+                .add(common);
+        }
+
+        summary
+    }
+
+    /// Countries sorted by accuracy, best first. Countries without a graded answer are skipped.
+    pub fn countries_by_accuracy(&self) -> Vec<(String, Tally)> {
+        let mut countries: Vec<(String, Tally)> = self
+            .per_country
+            .iter()
+            .filter(|(_, tally)| tally.graded > 0)
+            .map(|(code, tally)| (code.clone(), *tally))
+            .collect();
+
+        countries.sort_by(|(code_a, a), (code_b, b)| {
+            b.accuracy()
+                .partial_cmp(&a.accuracy())
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.graded.cmp(&a.graded))
+                .then(code_a.cmp(code_b))
+        });
+        countries
+    }
+}
+
+/// Consecutive days with at least one finished quiz, ending today or yesterday.
+pub fn current_streak(history: &[QuizStats], today: i64) -> u32 {
+    let days: BTreeSet<i64> = history
+        .iter()
+        .map(|stats| day_index(stats.get_common().finished_at_ms))
+        .collect();
+
+    let mut day = if days.contains(&today) {
+        today
+    } else {
+        today - 1
+    };
+    let mut streak = 0;
+    while days.contains(&day) {
+        streak += 1;
+        day -= 1;
+    }
+    streak
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::components::quiz::stats::country_shape::CountryShapeQuizStats;
+    use crate::utils::time::local_offset_ms;
+
+    const DAY_MS: u128 = 24 * 60 * 60 * 1000;
+    const TODAY: i64 = 20_000;
+
+    /// A quiz finished at local noon of the day, so it counts towards that day anywhere.
+    fn quiz(subject: &str, success: Option<bool>, day: i64, seconds: u128) -> QuizStats {
+        let noon = day as u128 * DAY_MS + DAY_MS / 2;
+        let finished_at_ms = (noon as i64 - local_offset_ms(noon)) as u128;
+        QuizStats::CountryShape(CountryShapeQuizStats {
+            common: QuizStatsCommon {
+                started_at_ms: finished_at_ms - seconds * 1000,
+                finished_at_ms,
+                subject: Some(subject.to_string()),
+                success,
+                attempts: 1,
+                answers: Vec::new(),
+            },
+            answer_match: None,
+            assisted: false,
+        })
+    }
+
+    fn tally(graded: usize, correct: usize) -> Tally {
+        Tally {
+            quizzes: graded,
+            graded,
+            correct,
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn tallies_quizzes() {
+        let mut tally = Tally::default();
+        assert_eq!(tally.accuracy(), None);
+        assert_eq!(tally.average_seconds(), None);
+
+        for (success, seconds) in [(Some(true), 4), (Some(false), 6), (None, 20)] {
+            tally.add(quiz("FR", success, TODAY, seconds).get_common());
+        }
+        assert_eq!(tally.quizzes, 3);
+        assert_eq!(tally.graded, 2);
+        assert_eq!(tally.correct, 1);
+        assert_eq!(tally.accuracy(), Some(50.0));
+        assert_eq!(tally.average_seconds(), Some(10.0));
+    }
+
+    #[test]
+    fn builds_summary_from_first_day() {
+        let history = [
+            quiz("FR", Some(true), TODAY - 10, 5),
+            quiz("FR", Some(false), TODAY - 1, 5),
+            quiz("DE", Some(true), TODAY, 5),
+            quiz("JP", Some(true), TODAY, 5),
+        ];
+        let summary = StatisticsSummary::build(&history, TODAY - 1);
+
+        assert_eq!(summary.overall.quizzes, 3);
+        assert_eq!(
+            summary.per_day.keys().copied().collect::<Vec<_>>(),
+            [TODAY - 1, TODAY]
+        );
+        assert_eq!(summary.per_day[&TODAY].quizzes, 2);
+        assert_eq!(summary.per_quiz_type[&QuizType::CountryShape].quizzes, 3);
+        assert_eq!(summary.per_region["Europe"].quizzes, 2);
+        assert_eq!(summary.per_region["Asia"].quizzes, 1);
+        assert_eq!(summary.per_country["FR"].correct, 0);
+    }
+
+    #[test]
+    fn sorts_countries_by_accuracy() {
+        let summary = StatisticsSummary {
+            per_country: HashMap::from([
+                ("FR".to_string(), tally(4, 2)),
+                ("DE".to_string(), tally(2, 2)),
+                ("IT".to_string(), tally(1, 1)),
+                ("ES".to_string(), tally(4, 2)),
+                ("PT".to_string(), Tally::default()),
+            ]),
+            ..StatisticsSummary::default()
+        };
+
+        let codes: Vec<String> = summary
+            .countries_by_accuracy()
+            .into_iter()
+            .map(|(code, _)| code)
+            .collect();
+        assert_eq!(codes, ["DE", "IT", "ES", "FR"]);
+    }
+
+    #[test]
+    fn counts_streak_ending_today_or_yesterday() {
+        let history: Vec<QuizStats> = [TODAY - 5, TODAY - 3, TODAY - 2, TODAY - 1, TODAY - 1]
+            .into_iter()
+            .map(|day| quiz("FR", Some(true), day, 5))
+            .collect();
+
+        assert_eq!(current_streak(&history, TODAY - 1), 3);
+        // Today doesn't break the streak before the first quiz of the day.
+        assert_eq!(current_streak(&history, TODAY), 3);
+        assert_eq!(current_streak(&history, TODAY + 1), 0);
+        assert_eq!(current_streak(&[], TODAY), 0);
+
+        let mut history = history;
+        history.push(quiz("FR", Some(false), TODAY, 5));
+        assert_eq!(current_streak(&history, TODAY), 4);
+    }
+}
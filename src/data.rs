@@ -1,21 +1,31 @@
+use crate::data::detail_levels::DETAIL_LEVELS;
+use crate::data::flag_colors::{build_flag_colors, FlagColorShare};
 use crate::data::identified_polygon::{IdentifiedPolygon, IdentifiedPolygonType};
-use crate::data::meshes::{CountryMeshes, CountryMeshesMap};
+use crate::data::meshes::{ProjectedWorldMesh, WorldMesh};
+use crate::data::neighbors::NeighborGraph;
 use crate::data::outlines::build_country_outlines;
 use crate::data::polygon_tree::build_polygon_tree;
+use crate::data::projection::Projection;
+use crate::data::regions::Regions;
 use eframe::emath::Vec2;
 use eframe::epaint::Shape;
-use egui::Image;
-use geo::{Scale, SimplifyVw};
+use egui::{Image, Pos2, Rect};
+use geo::{BoundingRect, Scale, SimplifyVw};
 use rstar::{PointDistance, RTree, AABB};
-use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex, PoisonError, Weak};
 use world_data::world_data_types::data::capital::Capital;
 use world_data::world_data_types::data::country::Country;
 
+pub mod detail_levels;
+pub mod flag_colors;
 pub mod identified_polygon;
-mod meshes;
-mod outlines;
+pub mod meshes;
+mod neighbors;
+pub mod outlines;
 mod polygon_tree;
+pub mod projection;
+mod regions;
 
 const EXCLUDED_COUNTRY_CODES: &[&str] = &["AQ"];
 const EXCLUDED_CAPITAL_NAMES: &[&str] = &["VATICAN CITY", "MONACO"];
@@ -30,11 +40,17 @@ const SCALED_COUNTRIES: &[(&str, f32)] = &[
 pub struct WorldStudyData {
     countries: HashMap<String, Arc<Country>>,
     country_codes: Vec<String>,
-    country_meshes: CountryMeshesMap,
+    regions: Regions,
+    /// One per detail level.
+    world_meshes: Vec<WorldMesh>,
+    /// Projected world meshes still held by a map.
+    projected_meshes: Mutex<Vec<Weak<ProjectedWorldMesh>>>,
     country_outlines: HashMap<String, Vec<Shape>>,
+    flag_colors: HashMap<String, Vec<FlagColorShare>>,
     capitals: HashMap<String, Arc<Capital>>,
-    capital_names: HashSet<String>,
-    polygon_tree: RTree<IdentifiedPolygon>,
+    /// One per detail level.
+    polygon_trees: Vec<RTree<IdentifiedPolygon>>,
+    neighbor_graph: NeighborGraph,
 }
 
 impl WorldStudyData {
@@ -43,22 +59,18 @@ impl WorldStudyData {
 
         let mut world_data = world_data::load();
         world_data.countries.values_mut().for_each(|country| {
-            let simplified_polygons = country
+            let scaling_factor = scaled_countries
+                .get(country.iso_a2.as_str())
+                .copied()
+                .unwrap_or(1.0);
+            country.polygons = country
                 .polygons
                 .iter()
-                .map(|poly| {
-                    let scaling_factor = scaled_countries
-                        .get(country.iso_a2.as_str())
-                        .copied()
-                        .unwrap_or(1.0);
-                    let scaled_poly = poly.scale(scaling_factor);
-                    scaled_poly.simplify_vw(&0.0025)
-                })
+                .map(|poly| poly.scale(scaling_factor))
                 .collect();
-            country.polygons = simplified_polygons;
         });
 
-        let countries: HashMap<String, Arc<Country>> = world_data
+        let detailed_countries: HashMap<String, Arc<Country>> = world_data
             .countries
             .iter()
             .map(|(code, country)| (code.clone(), Arc::new(country.clone())))
@@ -70,15 +82,28 @@ impl WorldStudyData {
                 }
             })
             .collect();
+        let detail_level_countries: Vec<HashMap<String, Arc<Country>>> = DETAIL_LEVELS
+            .iter()
+            .map(|level| simplify_countries(&detailed_countries, level.tolerance))
+            .collect();
+        let countries = detail_level_countries[0].clone();
 
         let mut countries_sorted: Vec<_> = countries.values().cloned().collect();
         countries_sorted.sort_by_key(|country| country.is_enclave);
-        let country_codes = countries_sorted
+        let country_codes: Vec<String> = countries_sorted
             .iter()
             .map(|country| country.iso_a2.clone())
             .collect();
-        let country_meshes = CountryMeshesMap::build(&countries);
+        let regions = Regions::build(&countries, &country_codes);
+        let world_meshes: Vec<WorldMesh> = detail_level_countries
+            .iter()
+            .zip(DETAIL_LEVELS)
+            .map(|(countries, level)| {
+                WorldMesh::build(countries, &country_codes, level.outline_width)
+            })
+            .collect();
         let country_outlines = build_country_outlines(&countries);
+        let flag_colors = build_flag_colors(&countries);
 
         let capitals: HashMap<String, Arc<Capital>> = world_data
             .capitals
@@ -92,18 +117,24 @@ impl WorldStudyData {
                 }
             })
             .collect();
-        let capital_names = capitals.keys().cloned().collect();
 
-        let polygon_tree = build_polygon_tree(&countries, &capitals);
+        let polygon_trees: Vec<RTree<IdentifiedPolygon>> = detail_level_countries
+            .iter()
+            .map(|countries| build_polygon_tree(countries, &capitals))
+            .collect();
+        let neighbor_graph = NeighborGraph::build(&countries, &polygon_trees[0]);
 
         Self {
             countries,
             country_codes,
-            country_meshes,
+            regions,
+            world_meshes,
+            projected_meshes: Mutex::default(),
             country_outlines,
+            flag_colors,
             capitals,
-            capital_names,
-            polygon_tree,
+            polygon_trees,
+            neighbor_graph,
         }
     }
 
@@ -130,6 +161,16 @@ impl WorldStudyData {
             .collect()
     }
 
+    pub fn get_capital_country(&self, capital_name: &str) -> Option<&Arc<Country>> {
+        let capital_name = capital_name.to_uppercase();
+        self.countries.values().find(|country| {
+            country
+                .capitals
+                .iter()
+                .any(|name| name.to_uppercase() == capital_name)
+        })
+    }
+
     pub fn get_countries(&self, country_codes: &[&str]) -> Vec<&Arc<Country>> {
         country_codes
             .iter()
@@ -145,17 +186,82 @@ impl WorldStudyData {
         &self.country_codes
     }
 
+    /// Continents mapped to their subregions, both sorted by name.
+    pub fn get_continents(&self) -> &BTreeMap<String, Vec<String>> {
+        self.regions.continents()
+    }
+
+    /// Codes of all countries in a continent or subregion.
+    pub fn countries_in_region(&self, region: &str) -> &[String] {
+        self.regions.countries_in_region(region)
+    }
+
+    pub fn get_country_subregion(&self, country_code: &str) -> Option<&str> {
+        self.regions
+            .subregion_of_country(&country_code.to_uppercase())
+    }
+
     pub fn get_country_outlines(&self, country_code: &str) -> Option<&Vec<Shape>> {
         self.country_outlines.get(country_code)
     }
 
-    pub fn get_country_meshes(&self, country_code: &str) -> Option<&CountryMeshes> {
-        self.country_meshes.get(country_code)
+    /// The coarse colours that appear in a country's flag.
+    pub fn get_flag_colors(&self, country_code: &str) -> &[FlagColorShare] {
+        self.flag_colors
+            .get(country_code)
+            .map(|colors| colors.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn get_world_mesh(&self, detail_level: usize) -> &WorldMesh {
+        &self.world_meshes[detail_level]
+    }
+
+    /// The world mesh of a detail level laid out by a projection, shared with every other map
+    /// currently using the same one.
+    pub fn get_projected_world_mesh(
+        &self,
+        detail_level: usize,
+        projection: Projection,
+    ) -> Arc<ProjectedWorldMesh> {
+        let mut projected_meshes = self
+            .projected_meshes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        projected_meshes.retain(|mesh| mesh.strong_count() > 0);
+
+        let existing = projected_meshes
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|mesh| mesh.detail_level == detail_level && mesh.projection == projection);
+        if let Some(mesh) = existing {
+            return mesh;
+        }
+
+        let mesh = Arc::new(self.world_meshes[detail_level].project(detail_level, projection));
+        projected_meshes.push(Arc::downgrade(&mesh));
+        mesh
+    }
+
+    /// Codes of the countries sharing a land border with the given country.
+    pub fn get_neighbors(&self, country_code: &str) -> &[String] {
+        self.neighbor_graph.get(&country_code.to_uppercase())
+    }
+
+    /// The shortest chain of neighbouring countries from one country to another, including both.
+    pub fn get_border_path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        self.neighbor_graph
+            .shortest_path(&from.to_uppercase(), &to.to_uppercase())
     }
 
-    pub fn get_polygon_id_at_point(&self, x: f32, y: f32) -> Option<&IdentifiedPolygon> {
+    pub fn get_polygon_id_at_point(
+        &self,
+        x: f32,
+        y: f32,
+        detail_level: usize,
+    ) -> Option<&IdentifiedPolygon> {
         let point_envelope = AABB::from_point([x, y]);
-        self.polygon_tree
+        self.polygon_trees[detail_level]
             .locate_in_envelope_intersecting(&point_envelope)
             .filter(|poly| poly.contains_point(&[x, y]))
             .max_by_key(|poly| {
@@ -163,7 +269,47 @@ impl WorldStudyData {
             })
     }
 
-    pub fn get_country_flag_image(&self, country_code: &str, size: Vec2) -> Option<Image> {
+    pub fn get_country_at_point(
+        &self,
+        x: f32,
+        y: f32,
+        detail_level: usize,
+    ) -> Option<&IdentifiedPolygon> {
+        let point_envelope = AABB::from_point([x, y]);
+        self.polygon_trees[detail_level]
+            .locate_in_envelope_intersecting(&point_envelope)
+            .filter(|poly| poly.polygon_type() == IdentifiedPolygonType::Country)
+            .filter(|poly| poly.contains_point(&[x, y]))
+            .max_by_key(|poly| poly.priority())
+    }
+
+    /// Codes of all countries with a polygon overlapping the given area in map coordinates.
+    pub fn get_countries_in_rect(&self, rect: Rect, detail_level: usize) -> HashSet<&str> {
+        let envelope = AABB::from_corners([rect.min.x, -rect.max.y], [rect.max.x, -rect.min.y]);
+        self.polygon_trees[detail_level]
+            .locate_in_envelope_intersecting(&envelope)
+            .filter(|poly| poly.polygon_type() == IdentifiedPolygonType::Country)
+            .map(|poly| poly.id())
+            .collect()
+    }
+
+    /// The bounding rectangle of all polygons of a country in map coordinates.
+    pub fn get_country_bounds(&self, country_code: &str) -> Option<Rect> {
+        let country = self.get_country(country_code)?;
+        country
+            .polygons
+            .iter()
+            .filter_map(|polygon| polygon.bounding_rect())
+            .map(|bounds| {
+                Rect::from_two_pos(
+                    Pos2::new(bounds.min().x, -bounds.min().y),
+                    Pos2::new(bounds.max().x, -bounds.max().y),
+                )
+            })
+            .reduce(|a, b| a.union(b))
+    }
+
+    pub fn get_country_flag_image(&self, country_code: &str, size: Vec2) -> Option<Image<'_>> {
         self.get_country(country_code).map(|country| {
             Image::from_bytes(
                 format!("bytes://flag_{}_{}x{}.svg", country.iso_a2, size.x, size.y),
@@ -173,3 +319,42 @@ impl WorldStudyData {
         })
     }
 }
+
+fn simplify_countries(
+    countries: &HashMap<String, Arc<Country>>,
+    tolerance: f32,
+) -> HashMap<String, Arc<Country>> {
+    countries
+        .iter()
+        .map(|(code, country)| {
+            let mut country = (**country).clone();
+            country.polygons = country
+                .polygons
+                .iter()
+                .map(|poly| poly.simplify_vw(&tolerance))
+                .collect();
+            (code.clone(), Arc::new(country))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::projection::Projection;
+    use crate::get_data;
+    use std::sync::Arc;
+
+    #[test]
+    fn shares_projected_meshes_while_held() {
+        let data = get_data();
+        let robinson = data.get_projected_world_mesh(0, Projection::Robinson);
+        let same = data.get_projected_world_mesh(0, Projection::Robinson);
+        let other_level = data.get_projected_world_mesh(1, Projection::Robinson);
+        assert!(Arc::ptr_eq(&robinson, &same));
+        assert!(!Arc::ptr_eq(&robinson, &other_level));
+
+        let released = Arc::downgrade(&robinson);
+        drop((robinson, same));
+        assert!(released.upgrade().is_none());
+    }
+}
@@ -0,0 +1,37 @@
+/// A precomputed simplification of the country geometry.
+#[derive(Debug, Clone, Copy)]
+pub struct DetailLevel {
+    /// Area tolerance of the Visvalingam-Whyatt simplification, in square degrees.
+    pub tolerance: f32,
+    /// Zoom in screen points per degree from which this level is used.
+    pub min_zoom: f32,
+    /// Outline width in degrees, finer levels are seen closer up and get thinner outlines.
+    pub outline_width: f32,
+}
+
+/// From coarse to fine, the first level is also used for everything that isn't drawn.
+pub const DETAIL_LEVELS: [DetailLevel; 3] = [
+    DetailLevel {
+        tolerance: 0.0025,
+        min_zoom: 0.0,
+        outline_width: 0.05,
+    },
+    DetailLevel {
+        tolerance: 0.0004,
+        min_zoom: 25.0,
+        outline_width: 0.015,
+    },
+    DetailLevel {
+        tolerance: 0.00005,
+        min_zoom: 120.0,
+        outline_width: 0.004,
+    },
+];
+
+/// Index of the finest level whose minimum zoom is reached.
+pub fn detail_level_for_zoom(zoom: f32) -> usize {
+    DETAIL_LEVELS
+        .iter()
+        .rposition(|level| zoom >= level.min_zoom)
+        .unwrap_or(0)
+}
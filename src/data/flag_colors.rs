@@ -0,0 +1,112 @@
+use egui::SizeHint;
+use egui_extras::image::load_svg_bytes_with_size;
+use std::collections::HashMap;
+use std::sync::Arc;
+use world_data::world_data_types::data::country::Country;
+
+/// Width flags are rasterised at to measure their colours, enough for the areas that matter.
+const SAMPLE_WIDTH: u32 = 48;
+/// Pixels more transparent than this are outside the flag's shape, e.g. Nepal's.
+const MIN_ALPHA: u8 = 128;
+
+/// Coarse colour categories used to compare flags with each other.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FlagColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+    White,
+    Black,
+    Grey,
+}
+
+impl FlagColor {
+    fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        let r = r as f32 / 255.0;
+        let g = g as f32 / 255.0;
+        let b = b as f32 / 255.0;
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+        if saturation < 0.2 {
+            return if max > 0.8 {
+                Self::White
+            } else if max < 0.25 {
+                Self::Black
+            } else {
+                Self::Grey
+            };
+        }
+        if max < 0.15 {
+            return Self::Black;
+        }
+
+        let hue = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        match hue {
+            h if !(15.0..340.0).contains(&h) => Self::Red,
+            h if h < 45.0 => Self::Orange,
+            h if h < 70.0 => Self::Yellow,
+            h if h < 170.0 => Self::Green,
+            h if h < 260.0 => Self::Blue,
+            _ => Self::Purple,
+        }
+    }
+}
+
+/// A colour of a flag and the share of the flag's area painted in it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FlagColorShare {
+    pub color: FlagColor,
+    pub share: f32,
+}
+
+/// Colours of a flag SVG weighted by painted area, largest first.
+///
+/// The flag is rasterised, so a coat of arms only counts for the few pixels it covers instead of
+/// adding every colour it's drawn with.
+pub fn extract_flag_colors(svg: &[u8]) -> Vec<FlagColorShare> {
+    let Ok(image) = load_svg_bytes_with_size(svg, Some(SizeHint::Width(SAMPLE_WIDTH))) else {
+        return Vec::new();
+    };
+
+    let mut counts: HashMap<FlagColor, usize> = HashMap::new();
+    for pixel in &image.pixels {
+        let [r, g, b, a] = pixel.to_srgba_unmultiplied();
+        if a >= MIN_ALPHA {
+            *counts.entry(FlagColor::from_rgb(r, g, b)).or_default() += 1;
+        }
+    }
+
+    let total: usize = counts.values().sum();
+    let mut shares: Vec<FlagColorShare> = counts
+        .into_iter()
+        .map(|(color, count)| FlagColorShare {
+            color,
+            share: count as f32 / total as f32,
+        })
+        .collect();
+    shares.sort_by(|a, b| b.share.total_cmp(&a.share).then(a.color.cmp(&b.color)));
+    shares
+}
+
+pub fn build_flag_colors(
+    countries: &HashMap<String, Arc<Country>>,
+) -> HashMap<String, Vec<FlagColorShare>> {
+    countries
+        .iter()
+        .map(|(code, country)| (code.clone(), extract_flag_colors(&country.flag_svg)))
+        .collect()
+}
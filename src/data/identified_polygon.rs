@@ -1,4 +1,4 @@
-use geo::{BoundingRect, Contains, EuclideanDistance, Point, Polygon};
+use geo::{BoundingRect, Contains, Distance, Euclidean, Point, Polygon};
 use rstar::{PointDistance, RTreeObject, AABB};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -58,7 +58,7 @@ impl RTreeObject for IdentifiedPolygon {
 impl PointDistance for IdentifiedPolygon {
     fn distance_2(&self, point: &[f32; 2]) -> f32 {
         let geo_point = Point::new(point[0], point[1]);
-        let distance = self.polygon.euclidean_distance(&geo_point);
+        let distance = Euclidean.distance(&self.polygon, &geo_point);
         distance * distance
     }
 
@@ -1,108 +1,240 @@
-use egui::{Color32, Pos2, Shape};
+use crate::data::projection::Projection;
+use egui::{Color32, Mesh, Pos2, Vec2};
 use geo::CoordsIter;
 use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Arc;
 use world_data::world_data_types::data::country::Country;
 
-#[derive(Debug, Default)]
-pub struct CountryMeshesMap(HashMap<String, CountryMeshes>);
-
-impl CountryMeshesMap {
-    pub fn get(&self, country_code: &str) -> Option<&CountryMeshes> {
-        self.0.get(country_code)
-    }
+/// The miter of sharper corners is cut short at this angle, so the outline doesn't spike.
+const MIN_MITER_COS: f32 = 0.25;
+
+/// Where a country's triangles and outline live in the [`WorldMesh`].
+#[derive(Debug, Default, Clone)]
+pub struct CountryMeshRanges {
+    pub fill_vertices: Range<usize>,
+    pub fill_indices: Range<usize>,
+    /// Vertex and index ranges of every polygon, in the same order as the polygons.
+    pub polygons: Vec<(Range<usize>, Range<usize>)>,
+    pub outline_vertices: Range<usize>,
+    pub outline_indices: Range<usize>,
+}
 
-    pub fn build(countries: &HashMap<String, Arc<Country>>) -> Self {
-        Self(CountryMeshes::build(countries))
-    }
+/// The vertex positions of a [`WorldMesh`] laid out by a projection, hidden ones end up at NaN.
+///
+/// Shared by every map showing the same detail level in the same projection, the maps only keep
+/// their own colours and indices.
+#[derive(Debug)]
+pub struct ProjectedWorldMesh {
+    pub detail_level: usize,
+    pub projection: Projection,
+    pub fill: Vec<Pos2>,
+    pub outline: Vec<Pos2>,
 }
 
+/// All country fills and outlines batched into two meshes, so the whole map is two shapes.
+///
+/// Countries are stored in drawing order, enclaves last. The meshes are uncoloured, they get the
+/// colours of the map theme when drawn.
 #[derive(Debug, Default)]
-pub struct CountryMeshes {
-    pub default: Vec<Shape>,
-    pub hovered: Vec<Shape>,
-    pub selected: Vec<Shape>,
+pub struct WorldMesh {
+    fill: Mesh,
+    outline: Mesh,
+    countries: HashMap<String, CountryMeshRanges>,
 }
 
-impl CountryMeshes {
-    pub fn build(countries: &HashMap<String, Arc<Country>>) -> HashMap<String, CountryMeshes> {
-        let mut country_meshes = HashMap::new();
-
-        for (code, country) in countries.iter() {
-            let mut default_meshes = Vec::new();
-            let mut hovered_meshes = Vec::new();
-            let mut selected_meshes = Vec::new();
+impl WorldMesh {
+    pub fn build(
+        countries: &HashMap<String, Arc<Country>>,
+        country_codes: &[String],
+        outline_width: f32,
+    ) -> Self {
+        let mut world_mesh = Self::default();
+
+        for code in country_codes {
+            let Some(country) = countries.get(code) else {
+                continue;
+            };
+
+            let fill_vertex_start = world_mesh.fill.vertices.len();
+            let fill_index_start = world_mesh.fill.indices.len();
+            let outline_vertex_start = world_mesh.outline.vertices.len();
+            let outline_index_start = world_mesh.outline.indices.len();
+            let mut polygons = Vec::new();
 
             for polygon in &country.polygons {
-                let mut all_points = Vec::new();
-                let mut flat_points = Vec::new();
-                let mut hole_indices = Vec::new();
-
-                let exterior = polygon.exterior();
-                let exterior_point_count = exterior.coords_count();
-
-                for coord in exterior.coords() {
-                    let x = coord.x;
-                    let y = -coord.y;
-                    all_points.push(Pos2::new(x, y));
-                    flat_points.push(x as f64);
-                    flat_points.push(y as f64);
-                }
-
-                let mut current_index = exterior_point_count;
-                for interior in polygon.interiors() {
-                    hole_indices.push(current_index);
-
-                    for coord in interior.coords() {
-                        let x = coord.x;
-                        let y = -coord.y;
-                        all_points.push(Pos2::new(x, y));
-                        flat_points.push(x as f64);
-                        flat_points.push(y as f64);
-                        current_index += 1;
-                    }
-                }
-
-                if let Ok(indices) = earcutr::earcut(&flat_points, &hole_indices, 2) {
-                    let create_mesh = |color: Color32| -> Shape {
-                        let mut mesh = egui::Mesh::default();
-                        mesh.vertices.reserve(all_points.len());
-
-                        for point in &all_points {
-                            mesh.vertices.push(egui::epaint::Vertex {
-                                pos: *point,
-                                uv: Pos2::ZERO,
-                                color,
-                            });
-                        }
-
-                        for chunk in indices.chunks(3) {
-                            if chunk.len() == 3 {
-                                mesh.indices.push(chunk[0] as u32);
-                                mesh.indices.push(chunk[1] as u32);
-                                mesh.indices.push(chunk[2] as u32);
-                            }
-                        }
-
-                        Shape::Mesh(Arc::from(mesh))
-                    };
-
-                    default_meshes.push(create_mesh(Color32::from_rgb(222, 163, 139)));
-                    hovered_meshes.push(create_mesh(Color32::from_rgb(249, 130, 132)));
-                    selected_meshes.push(create_mesh(Color32::from_rgb(255, 196, 132)));
-                }
+                let vertex_start = world_mesh.fill.vertices.len();
+                let index_start = world_mesh.fill.indices.len();
+                world_mesh.add_fill(polygon);
+                polygons.push((
+                    vertex_start..world_mesh.fill.vertices.len(),
+                    index_start..world_mesh.fill.indices.len(),
+                ));
+
+                let exterior: Vec<Pos2> = polygon
+                    .exterior()
+                    .coords()
+                    .map(|coord| Pos2::new(coord.x, -coord.y))
+                    .collect();
+                world_mesh.add_outline(&exterior, outline_width);
             }
 
-            country_meshes.insert(
+            world_mesh.countries.insert(
                 code.clone(),
-                CountryMeshes {
-                    default: default_meshes,
-                    hovered: hovered_meshes,
-                    selected: selected_meshes,
+                CountryMeshRanges {
+                    fill_vertices: fill_vertex_start..world_mesh.fill.vertices.len(),
+                    fill_indices: fill_index_start..world_mesh.fill.indices.len(),
+                    polygons,
+                    outline_vertices: outline_vertex_start..world_mesh.outline.vertices.len(),
+                    outline_indices: outline_index_start..world_mesh.outline.indices.len(),
                 },
             );
         }
 
-        country_meshes
+        world_mesh
+    }
+
+    pub fn fill(&self) -> &Mesh {
+        &self.fill
+    }
+
+    pub fn outline(&self) -> &Mesh {
+        &self.outline
+    }
+
+    pub fn project(&self, detail_level: usize, projection: Projection) -> ProjectedWorldMesh {
+        ProjectedWorldMesh {
+            detail_level,
+            projection,
+            fill: project_positions(&self.fill, &projection),
+            outline: project_positions(&self.outline, &projection),
+        }
+    }
+
+    pub fn get_country_ranges(&self, country_code: &str) -> Option<&CountryMeshRanges> {
+        self.countries.get(country_code)
     }
+
+    /// A standalone mesh of a single polygon of a country.
+    pub fn polygon_mesh(&self, country_code: &str, polygon_index: usize) -> Option<Mesh> {
+        let (vertices, indices) = self
+            .get_country_ranges(country_code)?
+            .polygons
+            .get(polygon_index)?;
+
+        Some(Mesh {
+            vertices: self.fill.vertices[vertices.clone()].to_vec(),
+            indices: self.fill.indices[indices.clone()]
+                .iter()
+                .map(|index| index - vertices.start as u32)
+                .collect(),
+            ..Mesh::default()
+        })
+    }
+
+    fn add_fill(&mut self, polygon: &geo::Polygon<f32>) {
+        let mut all_points = Vec::new();
+        let mut flat_points = Vec::new();
+        let mut hole_indices = Vec::new();
+
+        let exterior = polygon.exterior();
+        let exterior_point_count = exterior.coords_count();
+
+        for coord in exterior.coords() {
+            let x = coord.x;
+            let y = -coord.y;
+            all_points.push(Pos2::new(x, y));
+            flat_points.push(x as f64);
+            flat_points.push(y as f64);
+        }
+
+        let mut current_index = exterior_point_count;
+        for interior in polygon.interiors() {
+            hole_indices.push(current_index);
+
+            for coord in interior.coords() {
+                let x = coord.x;
+                let y = -coord.y;
+                all_points.push(Pos2::new(x, y));
+                flat_points.push(x as f64);
+                flat_points.push(y as f64);
+                current_index += 1;
+            }
+        }
+
+        // Polygons that can't be triangulated stay empty, their ranges keep the order intact.
+        let Ok(indices) = earcutr::earcut(&flat_points, &hole_indices, 2) else {
+            return;
+        };
+
+        let base = self.fill.vertices.len() as u32;
+        for point in all_points {
+            self.fill.colored_vertex(point, Color32::PLACEHOLDER);
+        }
+        for chunk in indices.chunks_exact(3) {
+            self.fill.add_triangle(
+                base + chunk[0] as u32,
+                base + chunk[1] as u32,
+                base + chunk[2] as u32,
+            );
+        }
+    }
+
+    /// Adds a closed line of constant width around the ring, two vertices per point.
+    fn add_outline(&mut self, ring: &[Pos2], width: f32) {
+        // Rings repeat their first point at the end.
+        let points = match ring.split_last() {
+            Some((last, rest)) if rest.first() == Some(last) => rest,
+            _ => ring,
+        };
+        if points.len() < 2 {
+            return;
+        }
+
+        let half_width = width / 2.0;
+        let count = points.len();
+        let base = self.outline.vertices.len() as u32;
+
+        for index in 0..count {
+            let point = points[index];
+            let previous = points[(index + count - 1) % count];
+            let next = points[(index + 1) % count];
+
+            let normal_in = (point - previous).normalized().rot90();
+            let normal_out = (next - point).normalized().rot90();
+            let miter = (normal_in + normal_out).normalized();
+            let miter = if miter == Vec2::ZERO {
+                normal_out
+            } else {
+                miter
+            };
+            let offset = miter * (half_width / miter.dot(normal_out).max(MIN_MITER_COS));
+
+            self.outline
+                .colored_vertex(point + offset, Color32::PLACEHOLDER);
+            self.outline
+                .colored_vertex(point - offset, Color32::PLACEHOLDER);
+        }
+
+        for index in 0..count as u32 {
+            let next = (index + 1) % count as u32;
+            let (outer, inner) = (base + 2 * index, base + 2 * index + 1);
+            let (next_outer, next_inner) = (base + 2 * next, base + 2 * next + 1);
+            self.outline.add_triangle(outer, inner, next_outer);
+            self.outline.add_triangle(inner, next_inner, next_outer);
+        }
+    }
+}
+
+/// Projects vertices given in `(longitude, -latitude)`.
+fn project_positions(mesh: &Mesh, projection: &Projection) -> Vec<Pos2> {
+    mesh.vertices
+        .iter()
+        .map(|vertex| match projection {
+            Projection::Equirectangular => vertex.pos,
+            _ => projection
+                .project(vertex.pos.x, -vertex.pos.y)
+                .unwrap_or(Pos2::new(f32::NAN, f32::NAN)),
+        })
+        .collect()
 }
@@ -0,0 +1,206 @@
+use crate::data::identified_polygon::{IdentifiedPolygon, IdentifiedPolygonType};
+use geo::CoordsIter;
+use rstar::primitives::{GeomWithData, Line};
+use rstar::RTree;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::Arc;
+use world_data::world_data_types::data::country::Country;
+
+/// How close two country outlines have to get to count as a shared border.
+/// The polygons are simplified independently, so shared borders rarely line up exactly.
+const BORDER_TOLERANCE: f32 = 0.02;
+
+/// Land-border adjacency between countries, derived from their polygons.
+#[derive(Debug, Default)]
+pub struct NeighborGraph(HashMap<String, Vec<String>>);
+
+impl NeighborGraph {
+    pub fn build(
+        countries: &HashMap<String, Arc<Country>>,
+        polygon_tree: &RTree<IdentifiedPolygon>,
+    ) -> Self {
+        let codes: Vec<&String> = countries.keys().collect();
+        let segment_tree = build_segment_tree(&codes, countries);
+        let tolerance_2 = BORDER_TOLERANCE * BORDER_TOLERANCE;
+        let mut neighbors: HashMap<String, BTreeSet<String>> = HashMap::new();
+        let mut connect = |a: &str, b: &str| {
+            if a != b {
+                neighbors
+                    .entry(a.to_string())
+                    .or_default()
+                    .insert(b.to_string());
+                neighbors
+                    .entry(b.to_string())
+                    .or_default()
+                    .insert(a.to_string());
+            }
+        };
+
+        for (index, code) in codes.iter().enumerate() {
+            for polygon in &countries[*code].polygons {
+                for coord in polygon.exterior().coords_iter() {
+                    for segment in
+                        segment_tree.locate_within_distance([coord.x, coord.y], tolerance_2)
+                    {
+                        if segment.data != index {
+                            connect(code, codes[segment.data]);
+                        }
+                    }
+                }
+
+                // Countries that overlap (e.g. scaled up micro states) don't need to have
+                // their outlines close to each other.
+                if let Some(coord) = polygon.exterior().0.first() {
+                    for poly in polygon_tree.locate_all_at_point(&[coord.x, coord.y]) {
+                        if poly.polygon_type() == IdentifiedPolygonType::Country {
+                            connect(code, poly.id());
+                        }
+                    }
+                }
+            }
+        }
+
+        Self(
+            neighbors
+                .into_iter()
+                .map(|(code, neighbors)| (code, neighbors.into_iter().collect()))
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, country_code: &str) -> &[String] {
+        self.0
+            .get(country_code)
+            .map(|neighbors| neighbors.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Breadth-first search for the shortest chain of land borders from one country to another,
+    /// including both ends. [`None`] if there is no land connection.
+    pub fn shortest_path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let mut previous: HashMap<&str, &str> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        previous.insert(from, from);
+
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![to.to_string()];
+                let mut step = to;
+                while step != from {
+                    step = previous[step];
+                    path.push(step.to_string());
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for neighbor in self.get(current) {
+                if !previous.contains_key(neighbor.as_str()) {
+                    previous.insert(neighbor, current);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// All outline segments, tagged with the index of their country in `codes`.
+fn build_segment_tree(
+    codes: &[&String],
+    countries: &HashMap<String, Arc<Country>>,
+) -> RTree<GeomWithData<Line<[f32; 2]>, usize>> {
+    let mut segments = Vec::new();
+    for (index, code) in codes.iter().enumerate() {
+        for polygon in &countries[*code].polygons {
+            let rings = std::iter::once(polygon.exterior()).chain(polygon.interiors());
+            for line in rings.flat_map(|ring| ring.lines()) {
+                let segment = Line::new([line.start.x, line.start.y], [line.end.x, line.end.y]);
+                segments.push(GeomWithData::new(segment, index));
+            }
+        }
+    }
+    RTree::bulk_load(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_data;
+
+    fn graph(edges: &[(&str, &str)]) -> NeighborGraph {
+        let mut neighbors: HashMap<String, Vec<String>> = HashMap::new();
+        for (a, b) in edges {
+            neighbors
+                .entry(a.to_string())
+                .or_default()
+                .push(b.to_string());
+            neighbors
+                .entry(b.to_string())
+                .or_default()
+                .push(a.to_string());
+        }
+        NeighborGraph(neighbors)
+    }
+
+    fn are_neighbors(a: &str, b: &str) -> bool {
+        let data = get_data();
+        let connected = data.get_neighbors(a).iter().any(|code| code == b);
+        assert_eq!(
+            connected,
+            data.get_neighbors(b).iter().any(|code| code == a),
+            "{a} and {b} should be neighbors both ways or not at all"
+        );
+        connected
+    }
+
+    #[test]
+    fn connects_enclaves_and_micro_states() {
+        for (a, b) in [("IT", "SM"), ("IT", "VA"), ("ZA", "LS"), ("FR", "MC")] {
+            assert!(are_neighbors(a, b), "{a} should border {b}");
+        }
+        assert_eq!(get_data().get_neighbors("LS"), ["ZA"]);
+    }
+
+    #[test]
+    fn connects_land_borders() {
+        for (a, b) in [("FR", "ES"), ("ES", "PT"), ("ES", "AD"), ("GB", "IE")] {
+            assert!(are_neighbors(a, b), "{a} should border {b}");
+        }
+    }
+
+    #[test]
+    fn ignores_borders_across_water() {
+        for (a, b) in [("GB", "FR"), ("IT", "TN"), ("JP", "KR"), ("DK", "NO")] {
+            assert!(!are_neighbors(a, b), "{a} shouldn't border {b}");
+        }
+    }
+
+    #[test]
+    fn finds_shortest_path() {
+        let graph = graph(&[
+            ("PT", "ES"),
+            ("ES", "FR"),
+            ("FR", "DE"),
+            ("FR", "BE"),
+            ("BE", "DE"),
+        ]);
+        assert_eq!(
+            graph.shortest_path("PT", "DE").unwrap(),
+            ["PT", "ES", "FR", "DE"]
+        );
+        assert_eq!(graph.shortest_path("ES", "ES").unwrap(), ["ES"]);
+        assert!(graph.shortest_path("PT", "IS").is_none());
+    }
+
+    #[test]
+    fn finds_shortest_path_over_land() {
+        let path = get_data()
+            .get_border_path("PT", "DE")
+            .expect("Portugal and Germany are connected over land");
+        assert_eq!(path.first().map(String::as_str), Some("PT"));
+        assert_eq!(path.last().map(String::as_str), Some("DE"));
+        assert_eq!(path.len(), 4);
+    }
+}
@@ -4,6 +4,11 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use world_data::world_data_types::data::country::Country;
 
+const OUTLINE_WIDTH: f32 = 0.05;
+
+/// One outline per country polygon, in the same order as the polygons.
+///
+/// Like the meshes, the outlines get the colours of the map theme when drawn.
 pub fn build_country_outlines(
     countries: &HashMap<String, Arc<Country>>,
 ) -> HashMap<String, Vec<Shape>> {
@@ -19,7 +24,7 @@ pub fn build_country_outlines(
 
             let shape = Shape::line(
                 outline_points,
-                Stroke::new(0.05, Color32::from_rgb(108, 86, 113)),
+                Stroke::new(OUTLINE_WIDTH, Color32::PLACEHOLDER),
             );
 
             shapes.push(shape);
@@ -0,0 +1,414 @@
+use egui::{Pos2, Rect};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+/// Map units per radian, so the equirectangular projection keeps longitude and latitude as is.
+const RADIUS: f32 = 180.0 / PI;
+/// Web Mercator is cut off here, the poles would be infinitely far away.
+const MERCATOR_MAX_LATITUDE: f32 = 85.0;
+
+/// Robinson's table of parallel lengths and distances from the equator, every 5° of latitude.
+const ROBINSON_X: [f32; 19] = [
+    1.0000, 0.9986, 0.9954, 0.9900, 0.9822, 0.9730, 0.9600, 0.9427, 0.9216, 0.8962, 0.8679, 0.8350,
+    0.7986, 0.7597, 0.7186, 0.6732, 0.6213, 0.5722, 0.5322,
+];
+const ROBINSON_Y: [f32; 19] = [
+    0.0000, 0.0620, 0.1240, 0.1860, 0.2480, 0.3100, 0.3720, 0.4340, 0.4958, 0.5571, 0.6176, 0.6769,
+    0.7346, 0.7903, 0.8435, 0.8936, 0.9394, 0.9761, 1.0000,
+];
+const ROBINSON_X_SCALE: f32 = 0.8487;
+const ROBINSON_Y_SCALE: f32 = 1.3523;
+
+const EQUAL_EARTH_A1: f32 = 1.340264;
+const EQUAL_EARTH_A2: f32 = -0.081106;
+const EQUAL_EARTH_A3: f32 = 0.000893;
+const EQUAL_EARTH_A4: f32 = 0.003796;
+
+/// Samples per edge when converting rectangles between map and geographic coordinates.
+const RECT_SAMPLES: usize = 16;
+
+/// How longitude and latitude are laid out on the map.
+///
+/// Geographic positions are `(longitude, latitude)` in degrees, map positions are in map units
+/// with y pointing south, like egui's screen coordinates.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Projection {
+    #[default]
+    Equirectangular,
+    WebMercator,
+    Robinson,
+    /// Equal-area, so country sizes can be compared.
+    EqualEarth,
+    /// A globe seen from space, only the hemisphere around the centre is visible.
+    Orthographic {
+        center_longitude: f32,
+        center_latitude: f32,
+    },
+}
+
+impl Projection {
+    pub const ALL: [Self; 5] = [
+        Self::Equirectangular,
+        Self::WebMercator,
+        Self::Robinson,
+        Self::EqualEarth,
+        Self::Orthographic {
+            center_longitude: 10.0,
+            center_latitude: 30.0,
+        },
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Equirectangular => "Equirectangular",
+            Self::WebMercator => "Web Mercator",
+            Self::Robinson => "Robinson",
+            Self::EqualEarth => "Equal Earth",
+            Self::Orthographic { .. } => "Globe",
+        }
+    }
+
+    /// Whether both are the same kind of projection, regardless of the globe's centre.
+    pub fn same_kind(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    pub fn is_globe(&self) -> bool {
+        matches!(self, Self::Orthographic { .. })
+    }
+
+    /// The map area that can be hovered and clicked, the globe's disk or the world without
+    /// Antarctica.
+    pub fn map_bounds(&self) -> Rect {
+        if self.is_globe() {
+            return Rect::from_center_size(Pos2::ZERO, egui::Vec2::splat(2.0 * RADIUS));
+        }
+        let world = Rect::from_two_pos(Pos2::new(-180.0, -90.0), Pos2::new(180.0, 60.0));
+        self.project_rect(world).unwrap_or(world)
+    }
+
+    /// Map position of a geographic position, `None` if it's on the far side of the globe.
+    pub fn project(&self, longitude: f32, latitude: f32) -> Option<Pos2> {
+        let lambda = longitude.to_radians();
+        let phi = latitude.clamp(-90.0, 90.0).to_radians();
+
+        let (x, y) = match *self {
+            Self::Equirectangular => (lambda, phi),
+            Self::WebMercator => {
+                let phi = phi.clamp(
+                    -MERCATOR_MAX_LATITUDE.to_radians(),
+                    MERCATOR_MAX_LATITUDE.to_radians(),
+                );
+                (lambda, (FRAC_PI_4 + phi / 2.0).tan().ln())
+            }
+            Self::Robinson => {
+                let (length, distance) = robinson_row(latitude.abs());
+                (
+                    ROBINSON_X_SCALE * length * lambda,
+                    ROBINSON_Y_SCALE * distance * phi.signum(),
+                )
+            }
+            Self::EqualEarth => {
+                let theta = (3f32.sqrt() / 2.0 * phi.sin()).asin();
+                let x = 2.0 * 3f32.sqrt() * lambda * theta.cos()
+                    / (3.0 * equal_earth_derivative(theta));
+                (x, equal_earth_y(theta))
+            }
+            Self::Orthographic {
+                center_longitude,
+                center_latitude,
+            } => {
+                let lambda = lambda - center_longitude.to_radians();
+                let phi0 = center_latitude.to_radians();
+                let cos_c = phi0.sin() * phi.sin() + phi0.cos() * phi.cos() * lambda.cos();
+                if cos_c < 0.0 {
+                    return None;
+                }
+                (
+                    phi.cos() * lambda.sin(),
+                    phi0.cos() * phi.sin() - phi0.sin() * phi.cos() * lambda.cos(),
+                )
+            }
+        };
+
+        Some(Pos2::new(x * RADIUS, -y * RADIUS))
+    }
+
+    /// Geographic position `(longitude, latitude)` of a map position, `None` if it's off the map.
+    pub fn unproject(&self, position: Pos2) -> Option<(f32, f32)> {
+        let x = position.x / RADIUS;
+        let y = -position.y / RADIUS;
+
+        let (lambda, phi) = match *self {
+            Self::Equirectangular => (x, y),
+            Self::WebMercator => (x, 2.0 * y.exp().atan() - FRAC_PI_2),
+            Self::Robinson => {
+                let distance = (y / ROBINSON_Y_SCALE).abs();
+                if distance > 1.0 {
+                    return None;
+                }
+                let latitude = robinson_latitude(distance);
+                let (length, _) = robinson_row(latitude);
+                (
+                    x / (ROBINSON_X_SCALE * length),
+                    latitude.to_radians() * y.signum(),
+                )
+            }
+            Self::EqualEarth => {
+                let theta = equal_earth_theta(y)?;
+                let lambda =
+                    3.0 * x * equal_earth_derivative(theta) / (2.0 * 3f32.sqrt() * theta.cos());
+                (
+                    lambda,
+                    (2.0 * theta.sin() / 3f32.sqrt()).clamp(-1.0, 1.0).asin(),
+                )
+            }
+            Self::Orthographic {
+                center_longitude,
+                center_latitude,
+            } => {
+                let rho = x.hypot(y);
+                if rho > 1.0 {
+                    return None;
+                }
+                let phi0 = center_latitude.to_radians();
+                let lambda0 = center_longitude.to_radians();
+                if rho == 0.0 {
+                    return Some((center_longitude, center_latitude));
+                }
+
+                let c = rho.asin();
+                let phi = (c.cos() * phi0.sin() + y * c.sin() * phi0.cos() / rho).asin();
+                let lambda = lambda0
+                    + (x * c.sin()).atan2(rho * c.cos() * phi0.cos() - y * c.sin() * phi0.sin());
+                (lambda, phi)
+            }
+        };
+
+        let longitude = wrap_longitude(lambda.to_degrees());
+        let latitude = phi.to_degrees();
+        if !self.is_globe() && lambda.abs() > PI + f32::EPSILON {
+            return None;
+        }
+        (latitude.abs() <= 90.0).then_some((longitude, latitude))
+    }
+
+    /// Map bounds of a rectangle given in `(longitude, -latitude)`, `None` if none of it is visible.
+    pub fn project_rect(&self, rect: Rect) -> Option<Rect> {
+        let points: Vec<Pos2> = rect_outline(rect)
+            .filter_map(|point| self.project(point.x, -point.y))
+            .collect();
+        (!points.is_empty()).then(|| Rect::from_points(&points))
+    }
+
+    /// Geographic bounds, in `(longitude, -latitude)`, of everything inside a map rectangle.
+    ///
+    /// Falls back to the whole world when part of the rectangle is off the map.
+    pub fn unproject_rect(&self, rect: Rect) -> Rect {
+        let world = Rect::from_min_max(Pos2::new(-180.0, -90.0), Pos2::new(180.0, 90.0));
+        let mut points = Vec::new();
+        for point in rect_outline(rect) {
+            let Some((longitude, latitude)) = self.unproject(point) else {
+                return world;
+            };
+            points.push(Pos2::new(longitude, -latitude));
+        }
+
+        // A rectangle crossing the antimeridian or containing a pole can't be sampled at its edges.
+        let bounds = Rect::from_points(&points);
+        let contains_pole = [90.0, -90.0]
+            .into_iter()
+            .filter_map(|latitude| self.project(0.0, latitude))
+            .any(|pole| rect.contains(pole));
+        if bounds.width() > 180.0 || contains_pole {
+            world
+        } else {
+            bounds.expand(1.0)
+        }
+    }
+}
+
+fn rect_outline(rect: Rect) -> impl Iterator<Item = Pos2> {
+    let corners = [
+        rect.left_top(),
+        rect.right_top(),
+        rect.right_bottom(),
+        rect.left_bottom(),
+    ];
+    (0..4).flat_map(move |edge| {
+        let from = corners[edge];
+        let to = corners[(edge + 1) % 4];
+        (0..RECT_SAMPLES).map(move |step| from.lerp(to, step as f32 / RECT_SAMPLES as f32))
+    })
+}
+
+fn wrap_longitude(longitude: f32) -> f32 {
+    (longitude + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Interpolated Robinson table row for a latitude between 0° and 90°.
+fn robinson_row(latitude: f32) -> (f32, f32) {
+    let position = (latitude / 5.0).clamp(0.0, 18.0);
+    let index = (position.floor() as usize).min(17);
+    let t = position - index as f32;
+    (
+        ROBINSON_X[index] + (ROBINSON_X[index + 1] - ROBINSON_X[index]) * t,
+        ROBINSON_Y[index] + (ROBINSON_Y[index + 1] - ROBINSON_Y[index]) * t,
+    )
+}
+
+/// Inverse of the Robinson distance column, for distances between 0 and 1.
+fn robinson_latitude(distance: f32) -> f32 {
+    let index = ROBINSON_Y
+        .windows(2)
+        .position(|pair| distance <= pair[1])
+        .unwrap_or(17);
+    let t = (distance - ROBINSON_Y[index]) / (ROBINSON_Y[index + 1] - ROBINSON_Y[index]);
+    (index as f32 + t) * 5.0
+}
+
+fn equal_earth_y(theta: f32) -> f32 {
+    let theta2 = theta * theta;
+    let theta6 = theta2 * theta2 * theta2;
+    theta
+        * (EQUAL_EARTH_A1
+            + EQUAL_EARTH_A2 * theta2
+            + theta6 * (EQUAL_EARTH_A3 + EQUAL_EARTH_A4 * theta2))
+}
+
+fn equal_earth_derivative(theta: f32) -> f32 {
+    let theta2 = theta * theta;
+    let theta6 = theta2 * theta2 * theta2;
+    EQUAL_EARTH_A1
+        + 3.0 * EQUAL_EARTH_A2 * theta2
+        + theta6 * (7.0 * EQUAL_EARTH_A3 + 9.0 * EQUAL_EARTH_A4 * theta2)
+}
+
+/// Solves `equal_earth_y(theta) = y` with Newton's method.
+fn equal_earth_theta(y: f32) -> Option<f32> {
+    let max_theta = (3f32.sqrt() / 2.0).asin();
+    if y.abs() > equal_earth_y(max_theta) + f32::EPSILON {
+        return None;
+    }
+
+    let mut theta = y / EQUAL_EARTH_A1;
+    for _ in 0..12 {
+        let delta = (equal_earth_y(theta) - y) / equal_earth_derivative(theta);
+        theta -= delta;
+        if delta.abs() < 1e-7 {
+            break;
+        }
+    }
+    Some(theta.clamp(-max_theta, max_theta))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f32 = 0.01;
+
+    /// Difference of two longitudes, so -180° and 180° count as the same.
+    fn longitude_difference(a: f32, b: f32) -> f32 {
+        wrap_longitude(a - b).abs()
+    }
+
+    fn assert_round_trip(projection: Projection, longitude: f32, latitude: f32) {
+        let position = projection
+            .project(longitude, latitude)
+            .unwrap_or_else(|| panic!("{projection:?} hides ({longitude}, {latitude})"));
+        let (lon, lat) = projection
+            .unproject(position)
+            .unwrap_or_else(|| panic!("{projection:?} can't unproject ({longitude}, {latitude})"));
+
+        assert!(
+            (lat - latitude).abs() < TOLERANCE,
+            "{projection:?}: latitude {latitude} came back as {lat}"
+        );
+        // Every longitude is the same place at the poles.
+        if latitude.abs() < 90.0 {
+            assert!(
+                longitude_difference(lon, longitude) < TOLERANCE,
+                "{projection:?}: longitude {longitude} came back as {lon}"
+            );
+        }
+    }
+
+    fn flat_projections() -> [Projection; 4] {
+        [
+            Projection::Equirectangular,
+            Projection::WebMercator,
+            Projection::Robinson,
+            Projection::EqualEarth,
+        ]
+    }
+
+    #[test]
+    fn round_trips_origin() {
+        for projection in Projection::ALL {
+            assert_round_trip(projection, 0.0, 0.0);
+        }
+        assert_eq!(
+            Projection::Equirectangular.project(0.0, 0.0),
+            Some(Pos2::ZERO)
+        );
+    }
+
+    #[test]
+    fn round_trips_antimeridian() {
+        for projection in flat_projections() {
+            for latitude in [-60.0, 0.0, 45.0] {
+                assert_round_trip(projection, 180.0, latitude);
+                assert_round_trip(projection, -180.0, latitude);
+                assert_round_trip(projection, 179.9, latitude);
+            }
+        }
+
+        let globe = Projection::Orthographic {
+            center_longitude: 170.0,
+            center_latitude: 0.0,
+        };
+        assert_round_trip(globe, 180.0, 0.0);
+        assert_round_trip(globe, -175.0, 20.0);
+    }
+
+    #[test]
+    fn round_trips_poles() {
+        for projection in [
+            Projection::Equirectangular,
+            Projection::Robinson,
+            Projection::EqualEarth,
+        ] {
+            assert_round_trip(projection, 0.0, 90.0);
+            assert_round_trip(projection, 0.0, -90.0);
+        }
+
+        assert_round_trip(Projection::WebMercator, 0.0, MERCATOR_MAX_LATITUDE);
+        assert_round_trip(Projection::WebMercator, 0.0, -MERCATOR_MAX_LATITUDE);
+        // Web Mercator can't reach the poles, they're clamped to its edge.
+        let pole = Projection::WebMercator.project(0.0, 90.0).unwrap();
+        let (_, latitude) = Projection::WebMercator.unproject(pole).unwrap();
+        assert!((latitude - MERCATOR_MAX_LATITUDE).abs() < TOLERANCE);
+
+        for center_latitude in [90.0, -90.0] {
+            let globe = Projection::Orthographic {
+                center_longitude: 0.0,
+                center_latitude,
+            };
+            assert_round_trip(globe, 0.0, center_latitude);
+            assert_round_trip(globe, 120.0, center_latitude / 2.0);
+        }
+    }
+
+    #[test]
+    fn hides_back_of_globe() {
+        let globe = Projection::Orthographic {
+            center_longitude: 10.0,
+            center_latitude: 30.0,
+        };
+        assert_eq!(globe.project(-170.0, -30.0), None);
+        assert_eq!(globe.project(0.0, -90.0), None);
+        assert!(globe.project(0.0, 90.0).is_some());
+        assert_eq!(globe.unproject(Pos2::new(RADIUS, RADIUS)), None);
+    }
+}
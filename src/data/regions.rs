@@ -0,0 +1,72 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use world_data::world_data_types::data::country::Country;
+
+/// Continents with their UN subregions and the countries in each of them.
+#[derive(Debug, Default)]
+pub struct Regions {
+    continents: BTreeMap<String, Vec<String>>,
+    region_countries: HashMap<String, Vec<String>>,
+    country_subregions: HashMap<String, String>,
+}
+
+impl Regions {
+    pub fn build(countries: &HashMap<String, Arc<Country>>, country_codes: &[String]) -> Self {
+        let mut regions = Self::default();
+
+        for code in country_codes {
+            let Some(country) = countries.get(code) else {
+                continue;
+            };
+            let subregion = subregion_of(country);
+
+            let subregions = regions
+                .continents
+                .entry(country.region.clone())
+                .or_default();
+            if !subregions.contains(&subregion) {
+                subregions.push(subregion.clone());
+            }
+
+            for region in [&country.region, &subregion] {
+                let region_countries = regions.region_countries.entry(region.clone()).or_default();
+                if !region_countries.contains(code) {
+                    region_countries.push(code.clone());
+                }
+            }
+
+            regions.country_subregions.insert(code.clone(), subregion);
+        }
+
+        regions
+            .continents
+            .values_mut()
+            .for_each(|subregions| subregions.sort());
+        regions
+    }
+
+    pub fn continents(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.continents
+    }
+
+    pub fn countries_in_region(&self, region: &str) -> &[String] {
+        self.region_countries
+            .get(region)
+            .map(|countries| countries.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn subregion_of_country(&self, country_code: &str) -> Option<&str> {
+        self.country_subregions
+            .get(country_code)
+            .map(|subregion| subregion.as_str())
+    }
+}
+
+/// Countries without a subregion are treated as their own continent-wide subregion.
+fn subregion_of(country: &Country) -> String {
+    country
+        .subregion
+        .clone()
+        .unwrap_or_else(|| country.region.clone())
+}
@@ -0,0 +1,8 @@
+use geo::{Coord, Distance, Haversine, Point};
+
+/// Great-circle distance between two (longitude, latitude) coordinates in kilometers.
+pub fn great_circle_distance_km(a: Coord<f32>, b: Coord<f32>) -> f64 {
+    let a = Point::new(a.x as f64, a.y as f64);
+    let b = Point::new(b.x as f64, b.y as f64);
+    Haversine.distance(a, b) / 1000.0
+}
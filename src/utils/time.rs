@@ -4,3 +4,142 @@ pub fn timestamp_ms() -> u128 {
         .unwrap()
         .as_millis()
 }
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Days since the unix epoch of a timestamp in milliseconds, counted in local time so a day ends
+/// at the user's midnight.
+pub fn day_index(timestamp_ms: u128) -> i64 {
+    local_day_index(timestamp_ms, local_offset_ms(timestamp_ms))
+}
+
+fn local_day_index(timestamp_ms: u128, offset_ms: i64) -> i64 {
+    (timestamp_ms as i64 + offset_ms).div_euclid(DAY_MS)
+}
+
+/// How far local time is ahead of UTC at the timestamp, daylight saving time included.
+///
+/// Falls back to UTC where the local time zone isn't known.
+pub fn local_offset_ms(timestamp_ms: u128) -> i64 {
+    let seconds = (timestamp_ms / 1000) as libc::time_t;
+    let Some(local) = local_time(seconds) else {
+        return 0;
+    };
+
+    let local_seconds = days_from_civil(
+        i64::from(local.tm_year) + 1900,
+        i64::from(local.tm_mon) + 1,
+        i64::from(local.tm_mday),
+    ) * 86_400
+        + i64::from(local.tm_hour) * 3600
+        + i64::from(local.tm_min) * 60
+        + i64::from(local.tm_sec);
+    (local_seconds - seconds as i64) * 1000
+}
+
+#[cfg(unix)]
+fn local_time(seconds: libc::time_t) -> Option<libc::tm> {
+    // SAFETY: both pointers are valid for the call, a null result means it failed.
+    unsafe {
+        let mut local: libc::tm = std::mem::zeroed();
+        (!libc::localtime_r(&seconds, &mut local).is_null()).then_some(local)
+    }
+}
+
+#[cfg(windows)]
+fn local_time(seconds: libc::time_t) -> Option<libc::tm> {
+    // SAFETY: both pointers are valid for the call, a non-zero result means it failed.
+    unsafe {
+        let mut local: libc::tm = std::mem::zeroed();
+        (libc::localtime_s(&mut local, &seconds) == 0).then_some(local)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn local_time(_seconds: libc::time_t) -> Option<libc::tm> {
+    None
+}
+
+/// Days since the unix epoch of a date, see http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Formats a day index as `YYYY-MM-DD`.
+pub fn format_day(day_index: i64) -> String {
+    // Civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = day_index + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR_MS: i64 = 60 * 60 * 1000;
+
+    #[test]
+    fn formats_days() {
+        assert_eq!(format_day(0), "1970-01-01");
+        assert_eq!(format_day(-1), "1969-12-31");
+        assert_eq!(format_day(19_723), "2024-01-01");
+        assert_eq!(format_day(19_782), "2024-02-29");
+        assert_eq!(format_day(11_017), "2000-03-01");
+        assert_eq!(format_day(47_541), "2100-03-01");
+        assert_eq!(format_day(-135_081), "1600-02-29");
+    }
+
+    #[test]
+    fn converts_dates_both_ways() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2024, 2, 29), 19_782);
+        for day in -700_000..700_000 {
+            let date = format_day(day);
+            let parts: Vec<i64> = date.split('-').map(|part| part.parse().unwrap()).collect();
+            assert_eq!(days_from_civil(parts[0], parts[1], parts[2]), day, "{date}");
+        }
+    }
+
+    #[test]
+    fn buckets_days_at_local_midnight() {
+        let midnight_utc = 19_723 * DAY_MS as u128;
+        assert_eq!(local_day_index(midnight_utc, 0), 19_723);
+        assert_eq!(local_day_index(midnight_utc - 1, 0), 19_722);
+
+        // 23:00 on new year's eve in New York is already the next day in UTC.
+        let evening_in_new_york = midnight_utc + 4 * HOUR_MS as u128;
+        assert_eq!(local_day_index(evening_in_new_york, -5 * HOUR_MS), 19_722);
+        // 08:00 in Tokyo is still the previous day in UTC.
+        let morning_in_tokyo = midnight_utc - HOUR_MS as u128;
+        assert_eq!(local_day_index(morning_in_tokyo, 9 * HOUR_MS), 19_723);
+    }
+
+    #[test]
+    fn uses_local_offset() {
+        let now = timestamp_ms();
+        let offset = local_offset_ms(now);
+        assert!(offset.abs() <= 14 * HOUR_MS);
+        assert_eq!(offset % (15 * 60 * 1000), 0);
+        assert_eq!(day_index(now), local_day_index(now, offset));
+    }
+}